@@ -0,0 +1,329 @@
+use bevy::prelude::*;
+
+use crate::{InitialTransform, TransformGizmoInteraction};
+
+/// Components of a direction smaller than this are treated as zero when matching handles to axes.
+const AXIS_EPSILON: f32 = 1e-4;
+
+/// Limits how the gizmo can transform an entity. Locked axes keep the value they had when the drag
+/// started, and translation and scale are clamped to the given bounds. Gizmo handles are greyed out
+/// when every selected entity locks all of the axes they would change.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct GizmoConstraints {
+    /// Space that the locked axes and bounds are expressed in.
+    pub space: ConstraintSpace,
+    pub lock_translation: BVec3,
+    /// Rotation about each axis, measured as Euler angles of the rotation applied by the drag.
+    pub lock_rotation: BVec3,
+    /// Scale along the entity's own axes.
+    pub lock_scale: BVec3,
+    pub min_translation: Vec3,
+    pub max_translation: Vec3,
+    pub min_scale: Vec3,
+    pub max_scale: Vec3,
+}
+
+impl Default for GizmoConstraints {
+    fn default() -> Self {
+        GizmoConstraints {
+            space: ConstraintSpace::default(),
+            lock_translation: BVec3::FALSE,
+            lock_rotation: BVec3::FALSE,
+            lock_scale: BVec3::FALSE,
+            min_translation: Vec3::NEG_INFINITY,
+            max_translation: Vec3::INFINITY,
+            min_scale: Vec3::ZERO,
+            max_scale: Vec3::INFINITY,
+        }
+    }
+}
+
+/// Makes a [`GizmoTransformable`](crate::GizmoTransformable) entity read-only, for example while
+/// it is being edited elsewhere. Locked entities can stay selected, but the gizmo doesn't move them
+/// and is placed on the rest of the selection. When the whole selection is locked, the gizmo is
+/// placed on it and every handle is greyed out.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GizmoLocked {
+    /// Why the entity can't be edited, for display by the application.
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConstraintSpace {
+    #[default]
+    World,
+    /// The space of the entity's parent, which is world space for entities without a parent.
+    Parent,
+}
+
+impl GizmoConstraints {
+    /// Constrains the world space transform `global` proposed for an entity, where `inverse_parent`
+    /// is the inverse of its parent's world space matrix.
+    pub(crate) fn constrain(
+        &self,
+        initial_transform: &InitialTransform,
+        inverse_parent: Mat4,
+        global: Transform,
+    ) -> Transform {
+        let (initial, proposed) = match self.space {
+            ConstraintSpace::World => (initial_transform.transform, global),
+            ConstraintSpace::Parent => (
+                initial_transform.local_transform,
+                Transform::from_matrix(inverse_parent * global.compute_matrix()),
+            ),
+        };
+        let translation = Vec3::select(
+            self.lock_translation,
+            initial.translation,
+            proposed.translation,
+        )
+        .clamp(self.min_translation, self.max_translation);
+        let (x, y, z) = (proposed.rotation * initial.rotation.inverse()).to_euler(EulerRot::XYZ);
+        let angles = Vec3::select(self.lock_rotation, Vec3::ZERO, Vec3::new(x, y, z));
+        let rotation =
+            Quat::from_euler(EulerRot::XYZ, angles.x, angles.y, angles.z) * initial.rotation;
+        let scale = Vec3::select(self.lock_scale, initial.scale, proposed.scale)
+            .clamp(self.min_scale, self.max_scale);
+        let constrained = Transform {
+            translation,
+            rotation,
+            scale,
+        };
+        match self.space {
+            ConstraintSpace::World => constrained,
+            ConstraintSpace::Parent => {
+                Transform::from_matrix(inverse_parent.inverse() * constrained.compute_matrix())
+            }
+        }
+    }
+
+    /// Returns `true` if these constraints lock every axis that `interaction` would change. The
+    /// interaction's axes are in world space, and are compared with locked axes in the constraint
+    /// space, whose rotation relative to the world is `parent_rotation` for
+    /// [`ConstraintSpace::Parent`], or with locked scale axes of the entity, whose world rotation is
+    /// `rotation`.
+    pub(crate) fn locks(
+        &self,
+        interaction: &TransformGizmoInteraction,
+        parent_rotation: Quat,
+        rotation: Quat,
+    ) -> bool {
+        let space_rotation = match self.space {
+            ConstraintSpace::World => Quat::IDENTITY,
+            ConstraintSpace::Parent => parent_rotation,
+        };
+        // Moving along `axis` changes every axis of `frame` it has a component on.
+        let axes = |axis: Vec3, frame: Quat| {
+            (frame.inverse() * axis.normalize_or_zero())
+                .abs()
+                .cmpgt(Vec3::splat(AXIS_EPSILON))
+        };
+        // Moving in the plane with `normal` changes every axis of `frame` except the one the
+        // normal lies along, if any.
+        let plane = |normal: Vec3, frame: Quat| {
+            !(frame.inverse() * normal.normalize_or_zero())
+                .abs()
+                .cmpgt(Vec3::splat(1.0 - AXIS_EPSILON))
+        };
+        let (locked, changed) = match *interaction {
+            TransformGizmoInteraction::TranslateAxis { axis, .. } => {
+                (self.lock_translation, axes(axis, space_rotation))
+            }
+            TransformGizmoInteraction::TranslatePlane { normal, .. } => {
+                (self.lock_translation, plane(normal, space_rotation))
+            }
+            TransformGizmoInteraction::TranslateSurface => (self.lock_translation, BVec3::TRUE),
+            TransformGizmoInteraction::RotateAxis { axis, .. } => {
+                (self.lock_rotation, axes(axis, space_rotation))
+            }
+            TransformGizmoInteraction::RotateFree => (self.lock_rotation, BVec3::TRUE),
+            TransformGizmoInteraction::ScaleAxis { axis, .. } => {
+                (self.lock_scale, axes(axis, rotation))
+            }
+            TransformGizmoInteraction::ScalePlane { normal, .. } => {
+                (self.lock_scale, plane(normal, rotation))
+            }
+            TransformGizmoInteraction::ScaleUniform => (self.lock_scale, BVec3::TRUE),
+        };
+        (locked | !changed).all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn initial(transform: Transform, local_transform: Transform) -> InitialTransform {
+        InitialTransform {
+            transform,
+            local_transform,
+            rotation_offset: Vec3::ZERO,
+        }
+    }
+
+    fn translate_axis(axis: Vec3) -> TransformGizmoInteraction {
+        TransformGizmoInteraction::TranslateAxis {
+            original: Vec3::X,
+            axis,
+        }
+    }
+
+    #[test]
+    fn constrain_locks_and_clamps_translation() {
+        let constraints = GizmoConstraints {
+            lock_translation: BVec3::new(true, false, false),
+            max_translation: Vec3::splat(2.0),
+            ..Default::default()
+        };
+        let initial = initial(Transform::IDENTITY, Transform::IDENTITY);
+        let constrained =
+            constraints.constrain(&initial, Mat4::IDENTITY, Transform::from_xyz(1.0, 1.0, 5.0));
+        assert!(constrained
+            .translation
+            .abs_diff_eq(Vec3::new(0.0, 1.0, 2.0), 1e-5));
+    }
+
+    #[test]
+    fn constrain_clamps_scale() {
+        let constraints = GizmoConstraints {
+            min_scale: Vec3::splat(0.5),
+            max_scale: Vec3::splat(2.0),
+            ..Default::default()
+        };
+        let initial = initial(Transform::IDENTITY, Transform::IDENTITY);
+        let constrained = constraints.constrain(
+            &initial,
+            Mat4::IDENTITY,
+            Transform::from_scale(Vec3::new(0.1, 1.0, 3.0)),
+        );
+        assert!(constrained
+            .scale
+            .abs_diff_eq(Vec3::new(0.5, 1.0, 2.0), 1e-5));
+    }
+
+    #[test]
+    fn constrain_locks_rotation_axes() {
+        let constraints = GizmoConstraints {
+            lock_rotation: BVec3::new(true, true, false),
+            ..Default::default()
+        };
+        let start = Quat::from_rotation_y(0.3);
+        let initial = initial(
+            Transform::from_rotation(start),
+            Transform::from_rotation(start),
+        );
+        // Rotating about a locked axis leaves the rotation unchanged.
+        let constrained = constraints.constrain(
+            &initial,
+            Mat4::IDENTITY,
+            Transform::from_rotation(Quat::from_rotation_x(0.5) * start),
+        );
+        assert!(constrained.rotation.abs_diff_eq(start, 1e-5));
+        // Rotating about the unlocked axis is kept.
+        let rotated = Quat::from_rotation_z(0.5) * start;
+        let constrained =
+            constraints.constrain(&initial, Mat4::IDENTITY, Transform::from_rotation(rotated));
+        assert!(constrained.rotation.abs_diff_eq(rotated, 1e-5));
+    }
+
+    #[test]
+    fn constrain_in_parent_space() {
+        let constraints = GizmoConstraints {
+            space: ConstraintSpace::Parent,
+            lock_translation: BVec3::new(true, false, false),
+            ..Default::default()
+        };
+        let parent =
+            Transform::from_xyz(10.0, 0.0, 0.0).with_rotation(Quat::from_rotation_y(FRAC_PI_2));
+        let initial = initial(parent, Transform::IDENTITY);
+        let inverse_parent = parent.compute_matrix().inverse();
+        // The parent's X axis points along -Z in world space.
+        let constrained = constraints.constrain(
+            &initial,
+            inverse_parent,
+            parent.with_translation(Vec3::new(10.0, 0.0, -2.0)),
+        );
+        assert!(constrained
+            .translation
+            .abs_diff_eq(Vec3::new(10.0, 0.0, 0.0), 1e-5));
+        // The parent's Z axis points along X in world space, and isn't locked.
+        let constrained = constraints.constrain(
+            &initial,
+            inverse_parent,
+            parent.with_translation(Vec3::new(12.0, 0.0, 0.0)),
+        );
+        assert!(constrained
+            .translation
+            .abs_diff_eq(Vec3::new(12.0, 0.0, 0.0), 1e-5));
+    }
+
+    #[test]
+    fn locks_compares_world_axes() {
+        let constraints = GizmoConstraints {
+            lock_translation: BVec3::new(true, false, false),
+            ..Default::default()
+        };
+        let identity = Quat::IDENTITY;
+        assert!(constraints.locks(&translate_axis(Vec3::X), identity, identity));
+        assert!(!constraints.locks(&translate_axis(Vec3::Y), identity, identity));
+        // A gizmo rotated away from the world axes also moves along Z.
+        let rotated = Quat::from_rotation_y(-0.2) * Vec3::X;
+        assert!(!constraints.locks(&translate_axis(rotated), identity, identity));
+        let constraints = GizmoConstraints {
+            lock_translation: BVec3::new(true, false, true),
+            ..constraints
+        };
+        assert!(constraints.locks(&translate_axis(rotated), identity, identity));
+    }
+
+    #[test]
+    fn locks_planes_and_uniform_handles() {
+        let constraints = GizmoConstraints {
+            lock_translation: BVec3::new(true, false, true),
+            lock_scale: BVec3::new(true, true, false),
+            ..Default::default()
+        };
+        let identity = Quat::IDENTITY;
+        let plane = |normal| TransformGizmoInteraction::TranslatePlane {
+            original: normal,
+            normal,
+        };
+        assert!(constraints.locks(&plane(Vec3::Y), identity, identity));
+        assert!(!constraints.locks(&plane(Vec3::X), identity, identity));
+        assert!(!constraints.locks(&TransformGizmoInteraction::ScaleUniform, identity, identity));
+    }
+
+    #[test]
+    fn locks_in_parent_space() {
+        let constraints = GizmoConstraints {
+            space: ConstraintSpace::Parent,
+            lock_translation: BVec3::new(true, false, false),
+            ..Default::default()
+        };
+        let parent_rotation = Quat::from_rotation_y(FRAC_PI_2);
+        assert!(constraints.locks(
+            &translate_axis(Vec3::NEG_Z),
+            parent_rotation,
+            parent_rotation
+        ));
+        assert!(!constraints.locks(&translate_axis(Vec3::X), parent_rotation, parent_rotation));
+    }
+
+    #[test]
+    fn locks_scale_on_entity_axes() {
+        let constraints = GizmoConstraints {
+            lock_scale: BVec3::new(true, false, false),
+            ..Default::default()
+        };
+        let rotation = Quat::from_rotation_y(FRAC_PI_2);
+        let scale_axis = |axis| TransformGizmoInteraction::ScaleAxis {
+            original: Vec3::X,
+            axis,
+        };
+        // The entity's X axis points along -Z in world space.
+        assert!(constraints.locks(&scale_axis(Vec3::NEG_Z), Quat::IDENTITY, rotation));
+        assert!(!constraints.locks(&scale_axis(Vec3::X), Quat::IDENTITY, rotation));
+    }
+}
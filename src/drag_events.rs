@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+
+use crate::{
+    InitialTransform, TransformGizmo, TransformGizmoCancelEvent, TransformGizmoInteraction,
+};
+
+/// An entity affected by a drag, with its transforms from when the drag started and its current
+/// transforms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DraggedEntity {
+    pub entity: Entity,
+    pub initial_local: Transform,
+    pub initial_global: GlobalTransform,
+    pub local: Transform,
+    pub global: GlobalTransform,
+}
+
+/// Sent when the selection starts moving, once the cursor has moved past the drag threshold or a
+/// modal transform has started.
+#[derive(Debug, Clone, Event)]
+pub struct GizmoDragStarted {
+    pub interaction: TransformGizmoInteraction,
+    pub entities: Vec<DraggedEntity>,
+}
+
+/// Sent every frame while a drag is in progress, after transforms have been propagated.
+#[derive(Debug, Clone, Event)]
+pub struct GizmoDragUpdated {
+    pub interaction: TransformGizmoInteraction,
+    pub entities: Vec<DraggedEntity>,
+    /// Translation, rotation, and scale applied so far, in the space of the gizmo when the drag
+    /// started.
+    pub delta: Transform,
+}
+
+/// Sent when a drag is committed. The entities hold their final transforms.
+#[derive(Debug, Clone, Event)]
+pub struct GizmoDragEnded {
+    pub interaction: TransformGizmoInteraction,
+    pub entities: Vec<DraggedEntity>,
+    /// Translation, rotation, and scale applied by the drag, in the space of the gizmo when the
+    /// drag started.
+    pub delta: Transform,
+}
+
+/// Sent when a drag is cancelled. The entities have been restored to their initial transforms.
+#[derive(Debug, Clone, Event)]
+pub struct GizmoDragCancelled {
+    pub interaction: TransformGizmoInteraction,
+    pub entities: Vec<DraggedEntity>,
+}
+
+/// The drag in progress, as of the last frame.
+#[derive(Default)]
+pub(crate) struct DragState {
+    drag: Option<(TransformGizmoInteraction, Vec<DraggedEntity>, Transform)>,
+}
+
+/// Sends the drag lifecycle events, by comparing the state of the gizmo with the previous frame.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn send_drag_events(
+    mut state: Local<DragState>,
+    gizmo_query: Query<&TransformGizmo>,
+    dragged_query: Query<(Entity, &Transform, &GlobalTransform, &InitialTransform)>,
+    transform_query: Query<(&Transform, &GlobalTransform)>,
+    mut cancel_events: EventReader<TransformGizmoCancelEvent>,
+    mut started_events: EventWriter<GizmoDragStarted>,
+    mut updated_events: EventWriter<GizmoDragUpdated>,
+    mut ended_events: EventWriter<GizmoDragEnded>,
+    mut cancelled_events: EventWriter<GizmoDragCancelled>,
+) {
+    let cancelled = cancel_events.read().count() > 0;
+    let Ok(gizmo) = gizmo_query.get_single() else {
+        return;
+    };
+    // Nothing is reported while no entity is being dragged.
+    let interaction = gizmo
+        .current_interaction
+        .filter(|_| gizmo.initial_transform.is_some() && !dragged_query.is_empty());
+
+    let Some(interaction) = interaction else {
+        let Some((interaction, mut entities, delta)) = state.drag.take() else {
+            return;
+        };
+        // Entities that were despawned during the drag keep their last known transforms.
+        for dragged in entities.iter_mut() {
+            if let Ok((local, global)) = transform_query.get(dragged.entity) {
+                dragged.local = *local;
+                dragged.global = *global;
+            }
+        }
+        if cancelled {
+            cancelled_events.send(GizmoDragCancelled {
+                interaction,
+                entities,
+            });
+        } else {
+            ended_events.send(GizmoDragEnded {
+                interaction,
+                entities,
+                delta,
+            });
+        }
+        return;
+    };
+
+    let entities: Vec<_> = dragged_query
+        .iter()
+        .map(|(entity, local, global, initial)| DraggedEntity {
+            entity,
+            initial_local: initial.local_transform,
+            initial_global: initial.transform.into(),
+            local: *local,
+            global: *global,
+        })
+        .collect();
+    if state.drag.is_none() {
+        started_events.send(GizmoDragStarted {
+            interaction,
+            entities: entities.clone(),
+        });
+    }
+    updated_events.send(GizmoDragUpdated {
+        interaction,
+        entities: entities.clone(),
+        delta: gizmo.delta,
+    });
+    state.drag = Some((interaction, entities, gizmo.delta));
+}
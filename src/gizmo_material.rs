@@ -1,4 +1,5 @@
 use bevy::{
+    color::Luminance,
     pbr::{MaterialPipeline, MaterialPipelineKey},
     prelude::*,
     reflect::TypePath,
@@ -8,6 +9,15 @@ use bevy::{
             AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
         },
     },
+    utils::HashMap,
+};
+use bevy_mod_picking::{prelude::PickingInteraction, selection::PickSelection};
+
+use crate::{
+    constraints::{GizmoConstraints, GizmoLocked},
+    mesh::RotationSweepGizmo,
+    style::GizmoStyle,
+    GizmoTransformable, TransformGizmo, TransformGizmoInteraction,
 };
 
 pub const GIZMO_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(13953800272683943019);
@@ -36,7 +46,11 @@ impl Material for GizmoMaterial {
     }
 
     fn alpha_mode(&self) -> AlphaMode {
-        AlphaMode::Opaque
+        if self.color.alpha < 1.0 {
+            AlphaMode::Blend
+        } else {
+            AlphaMode::Opaque
+        }
     }
 
     fn specialize(
@@ -49,3 +63,162 @@ impl Material for GizmoMaterial {
         Ok(())
     }
 }
+
+/// Material a gizmo part was built with, used when no state material replaces it.
+#[derive(Component)]
+pub(crate) struct BaseMaterial(Handle<GizmoMaterial>);
+
+/// Materials swapped onto gizmo parts to show the state of the gizmo.
+pub(crate) struct StateMaterials {
+    error: Handle<GizmoMaterial>,
+    locked: Handle<GizmoMaterial>,
+    /// Highlighted and dimmed variants of each base material, created when first needed.
+    highlighted: HashMap<AssetId<GizmoMaterial>, Handle<GizmoMaterial>>,
+    dimmed: HashMap<AssetId<GizmoMaterial>, Handle<GizmoMaterial>>,
+}
+
+/// Returns the variant of `base` stored in `variants`, adding it to `materials` with its color
+/// changed by `change` if it doesn't exist yet.
+fn material_variant(
+    variants: &mut HashMap<AssetId<GizmoMaterial>, Handle<GizmoMaterial>>,
+    materials: &mut ResMut<Assets<GizmoMaterial>>,
+    base: &Handle<GizmoMaterial>,
+    change: impl Fn(Hsla) -> Hsla,
+) -> Handle<GizmoMaterial> {
+    variants
+        .entry(base.id())
+        .or_insert_with(|| {
+            let color = materials
+                .get(base)
+                .map_or(Color::WHITE, |material| material.color.into());
+            materials.add(GizmoMaterial::from(Color::from(change(color.into()))))
+        })
+        .clone()
+}
+
+/// Returns `true` if `a` and `b` are started by the same handle. The axes of a handle follow the
+/// gizmo's orientation, so handles are told apart by their original axis instead.
+fn same_handle(a: &TransformGizmoInteraction, b: &TransformGizmoInteraction) -> bool {
+    use TransformGizmoInteraction::*;
+    match (*a, *b) {
+        (TranslateAxis { original: a, .. }, TranslateAxis { original: b, .. })
+        | (TranslatePlane { original: a, .. }, TranslatePlane { original: b, .. })
+        | (RotateAxis { original: a, .. }, RotateAxis { original: b, .. })
+        | (ScaleAxis { original: a, .. }, ScaleAxis { original: b, .. })
+        | (ScalePlane { original: a, .. }, ScalePlane { original: b, .. }) => a == b,
+        // Surface translation is started from the view translate handle.
+        (TranslateSurface, TranslatePlane { original, .. })
+        | (TranslatePlane { original, .. }, TranslateSurface) => original == Vec3::ZERO,
+        (a, b) => std::mem::discriminant(&a) == std::mem::discriminant(&b),
+    }
+}
+
+/// Swaps the materials of the gizmo parts to show the state of the gizmo: every part is tinted
+/// while the current drag is rejected, and handles are greyed out when every selected entity's
+/// [`GizmoConstraints`] lock the axes they control. Every part of the hovered or dragged handle is
+/// highlighted, and the other handles are dimmed while one is dragged.
+pub(crate) fn update_handle_materials(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<GizmoMaterial>>,
+    style: Res<GizmoStyle>,
+    gizmo_query: Query<(&TransformGizmo, &PickingInteraction, &Children)>,
+    mut part_query: Query<
+        (
+            Entity,
+            &mut Handle<GizmoMaterial>,
+            Option<&BaseMaterial>,
+            Option<&TransformGizmoInteraction>,
+        ),
+        Without<RotationSweepGizmo>,
+    >,
+    selection_query: Query<
+        (
+            &PickSelection,
+            Option<&GizmoConstraints>,
+            Has<GizmoLocked>,
+            &Transform,
+            &GlobalTransform,
+        ),
+        With<GizmoTransformable>,
+    >,
+    mut state_materials: Local<Option<StateMaterials>>,
+) {
+    // The base materials are replaced when the style changes, so the variants are too.
+    if style.is_changed() {
+        *state_materials = None;
+    }
+    let state_materials = state_materials.get_or_insert_with(|| StateMaterials {
+        error: materials.add(GizmoMaterial::from(style.rejected_color)),
+        locked: materials.add(GizmoMaterial::from(style.locked_color)),
+        highlighted: HashMap::default(),
+        dimmed: HashMap::default(),
+    });
+    let selected_constraints: Vec<_> = selection_query
+        .iter()
+        .filter(|(selection, ..)| selection.is_selected)
+        .map(|(_, constraints, locked, transform, global_transform)| {
+            let rotation = global_transform.to_scale_rotation_translation().1;
+            let parent_rotation = rotation * transform.rotation.inverse();
+            (constraints, locked, parent_rotation, rotation)
+        })
+        .collect();
+    // A locked entity can't be changed by any handle.
+    let locked = |interaction: &TransformGizmoInteraction| {
+        !selected_constraints.is_empty()
+            && selected_constraints.iter().all(
+                |(constraints, locked, parent_rotation, rotation)| {
+                    *locked
+                        || constraints.is_some_and(|constraints| {
+                            constraints.locks(interaction, *parent_rotation, *rotation)
+                        })
+                },
+            )
+    };
+    for (gizmo, interaction, children) in gizmo_query.iter() {
+        let rejected = gizmo.rejected && gizmo.is_active();
+        // Modal transforms aren't started from a handle, so nothing is highlighted during them.
+        let dragged = gizmo.is_active() && gizmo.modal.is_none();
+        let hovered = *interaction == PickingInteraction::Hovered && gizmo.modal.is_none();
+        let current = gizmo.current_interaction.filter(|_| dragged || hovered);
+        let mut parts = part_query.iter_many_mut(children);
+        while let Some((entity, mut material, base_material, part_interaction)) = parts.fetch_next()
+        {
+            let base_material = match base_material {
+                Some(BaseMaterial(base_material)) => base_material.clone(),
+                None => {
+                    commands
+                        .entity(entity)
+                        .insert(BaseMaterial(material.clone()));
+                    material.clone()
+                }
+            };
+            let is_current = part_interaction
+                .zip(current.as_ref())
+                .is_some_and(|(part, current)| same_handle(part, current));
+            let target = if rejected {
+                state_materials.error.clone()
+            } else if part_interaction.is_some_and(locked) {
+                state_materials.locked.clone()
+            } else if is_current {
+                material_variant(
+                    &mut state_materials.highlighted,
+                    &mut materials,
+                    &base_material,
+                    |color| color.lighter(style.highlight_lightness),
+                )
+            } else if dragged && part_interaction.is_some() {
+                material_variant(
+                    &mut state_materials.dimmed,
+                    &mut materials,
+                    &base_material,
+                    |color| color.darker(style.dim_lightness),
+                )
+            } else {
+                base_material
+            };
+            if *material != target {
+                *material = target;
+            }
+        }
+    }
+}
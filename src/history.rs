@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{GizmoSettings, TransformGizmo, TransformTarget};
+
+/// The local transform of an entity before and after an edit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransformChange {
+    pub entity: Entity,
+    pub from: Transform,
+    pub to: Transform,
+}
+
+/// Undo and redo history of the edits made with the gizmo. Every completed drag or modal transform
+/// is recorded as one entry. Undoing or redoing is applied at the start of the next frame, and is
+/// deferred while the gizmo is being dragged.
+#[derive(Resource, Clone, Debug)]
+pub struct GizmoHistory {
+    /// Maximum number of entries kept. The oldest entries are dropped first.
+    pub depth: usize,
+    /// Pressing this key while holding one of `modifier_keys` undoes the last edit. Also holding
+    /// `Shift` redoes instead.
+    pub undo_key: Option<KeyCode>,
+    /// Keys that enable `undo_key`, such as the left and right `Ctrl` keys. If empty, `undo_key`
+    /// works on its own.
+    pub modifier_keys: Vec<KeyCode>,
+    entries: VecDeque<Vec<TransformChange>>,
+    // Number of entries, counted from the newest, that have been undone and can be redone.
+    undone: usize,
+    // Transforms waiting to be applied by an undo or redo.
+    pending: Vec<(Entity, Transform)>,
+}
+
+impl Default for GizmoHistory {
+    fn default() -> Self {
+        GizmoHistory {
+            depth: 100,
+            undo_key: Some(KeyCode::KeyZ),
+            modifier_keys: vec![KeyCode::ControlLeft, KeyCode::ControlRight],
+            entries: VecDeque::new(),
+            undone: 0,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl GizmoHistory {
+    /// Records an edit, discarding any edits that were undone. Changes that leave the transform
+    /// as it was are ignored.
+    pub fn push(&mut self, mut changes: Vec<TransformChange>) {
+        changes.retain(|change| change.from != change.to);
+        if changes.is_empty() {
+            return;
+        }
+        self.entries.truncate(self.entries.len() - self.undone);
+        self.undone = 0;
+        self.entries.push_back(changes);
+        while self.entries.len() > self.depth {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Restores the entities changed by the last edit to their transforms from before it. Returns
+    /// `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if !self.can_undo() {
+            return false;
+        }
+        self.undone += 1;
+        let changes = &self.entries[self.entries.len() - self.undone];
+        self.pending
+            .extend(changes.iter().map(|change| (change.entity, change.from)));
+        true
+    }
+
+    /// Reapplies the last undone edit. Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        if !self.can_redo() {
+            return false;
+        }
+        let changes = &self.entries[self.entries.len() - self.undone];
+        self.undone -= 1;
+        self.pending
+            .extend(changes.iter().map(|change| (change.entity, change.to)));
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undone < self.entries.len()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.undone > 0
+    }
+
+    /// Removes every entry from the history, including undos and redos that haven't been applied.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.undone = 0;
+        self.pending.clear();
+    }
+}
+
+/// Handles the undo and redo key bindings, and applies the transforms of undone or redone edits.
+pub(crate) fn apply_gizmo_history(
+    mut commands: Commands,
+    plugin_settings: Res<GizmoSettings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<GizmoHistory>,
+    gizmo_query: Query<&TransformGizmo>,
+    mut transform_query: Query<(&mut Transform, Option<&Parent>), Without<TransformGizmo>>,
+    parent_query: Query<&GlobalTransform>,
+) {
+    if gizmo_query.iter().any(TransformGizmo::is_active) {
+        return;
+    }
+    let modifier =
+        history.modifier_keys.is_empty() || keys.any_pressed(history.modifier_keys.iter().copied());
+    if modifier && history.undo_key.is_some_and(|key| keys.just_pressed(key)) {
+        if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            history.redo();
+        } else {
+            history.undo();
+        }
+    }
+    // Avoid triggering change detection on the history every frame.
+    if history.pending.is_empty() {
+        return;
+    }
+    let mut hook_targets = Vec::new();
+    for (entity, transform) in std::mem::take(&mut history.pending) {
+        // Entities that have been despawned since the edit are skipped.
+        let Ok((mut current, parent)) = transform_query.get_mut(entity) else {
+            continue;
+        };
+        if plugin_settings.transform_hook.is_none() {
+            current.set_if_neq(transform);
+            continue;
+        }
+        let parent_transform = parent
+            .and_then(|parent| parent_query.get(parent.get()).ok())
+            .copied()
+            .unwrap_or_default();
+        hook_targets.push(TransformTarget {
+            entity,
+            local: transform,
+            global: parent_transform
+                .mul_transform(transform)
+                .compute_transform(),
+        });
+    }
+    if let Some(transform_hook) = plugin_settings.transform_hook {
+        commands.run_system_with_input(transform_hook, hook_targets);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(index: u32, from: f32, to: f32) -> TransformChange {
+        TransformChange {
+            entity: Entity::from_raw(index),
+            from: Transform::from_xyz(from, 0.0, 0.0),
+            to: Transform::from_xyz(to, 0.0, 0.0),
+        }
+    }
+
+    #[test]
+    fn push_discards_undone_entries() {
+        let mut history = GizmoHistory::default();
+        history.push(vec![change(0, 0.0, 1.0)]);
+        history.push(vec![change(0, 1.0, 2.0)]);
+        assert!(history.undo());
+        assert!(history.can_redo());
+
+        history.push(vec![change(0, 1.0, 3.0)]);
+        assert!(!history.can_redo());
+        assert!(!history.redo());
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(history.entries[1], vec![change(0, 1.0, 3.0)]);
+    }
+
+    #[test]
+    fn push_drops_entries_past_depth() {
+        let mut history = GizmoHistory {
+            depth: 2,
+            ..Default::default()
+        };
+        for i in 0..3 {
+            history.push(vec![change(0, i as f32, i as f32 + 1.0)]);
+        }
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(history.entries[0], vec![change(0, 1.0, 2.0)]);
+        assert!(history.undo());
+        assert!(history.undo());
+        assert!(!history.undo());
+    }
+
+    #[test]
+    fn push_ignores_unchanged_transforms() {
+        let mut history = GizmoHistory::default();
+        history.push(vec![change(0, 1.0, 1.0)]);
+        assert!(!history.can_undo());
+
+        history.push(vec![change(0, 1.0, 1.0), change(1, 0.0, 1.0)]);
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.entries[0], vec![change(1, 0.0, 1.0)]);
+    }
+
+    #[test]
+    fn clear_discards_pending_transforms() {
+        let mut history = GizmoHistory::default();
+        history.push(vec![change(0, 0.0, 1.0)]);
+        assert!(history.undo());
+        assert_eq!(history.pending.len(), 1);
+
+        history.clear();
+        assert!(history.pending.is_empty());
+        assert!(!history.can_undo());
+        assert!(!history.can_redo());
+    }
+}
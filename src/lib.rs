@@ -1,21 +1,37 @@
 #![allow(clippy::type_complexity)]
 
 use bevy::asset::load_internal_asset;
-use bevy::{prelude::*, render::camera::Projection, transform::TransformSystem};
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    render::{camera::Projection, primitives::Aabb},
+    transform::TransformSystem,
+};
 use bevy_mod_picking::{
     backend::{HitData, PointerHits},
     picking_core::PickSet,
-    prelude::{PickingInteraction, PointerId},
+    prelude::{Pickable, PickingInteraction, PointerId},
     selection::{NoDeselect, PickSelection},
 };
-use bevy_mod_raycast::prelude::RaycastSystem;
+use bevy_mod_raycast::prelude::{Raycast, RaycastMethod, RaycastSettings, RaycastSystem};
 use gizmo_material::GizmoMaterial;
-use mesh::{RotationGizmo, ViewTranslateGizmo};
+use mesh::{RotationGizmo, RotationSweepGizmo, ScaleGizmo, ViewAlignedGizmo};
+use modal::ModalTransform;
 use normalization::*;
+use numeric::NumericInput;
+use validation::ProposedTransforms;
 
+pub mod constraints;
+pub mod drag_events;
 mod gizmo_material;
+pub mod history;
 mod mesh;
+pub mod modal;
 pub mod normalization;
+mod numeric;
+pub mod snapping;
+pub mod style;
+pub mod validation;
 
 pub mod picking;
 
@@ -25,7 +41,16 @@ pub use picking::{GizmoPickSource, PickableGizmo};
 #[derive(Resource, Clone, Debug)]
 pub struct GizmoSystemsEnabled(pub bool);
 
+pub use constraints::{ConstraintSpace, GizmoConstraints, GizmoLocked};
+pub use drag_events::{
+    DraggedEntity, GizmoDragCancelled, GizmoDragEnded, GizmoDragStarted, GizmoDragUpdated,
+};
+pub use history::{GizmoHistory, TransformChange};
+pub use modal::{ModalKeys, ModalMode};
 pub use normalization::Ui3dNormalization;
+pub use snapping::{GizmoSnapping, SnapMode, VertexSnapSource};
+pub use style::GizmoStyle;
+pub use validation::TransformValidation;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum TransformGizmoSystem {
@@ -37,7 +62,11 @@ pub enum TransformGizmoSystem {
     AdjustViewTranslateGizmo,
     Place,
     Hover,
+    Modal,
+    NumericEntry,
     Grab,
+    Cancel,
+    History,
     Drag,
 }
 
@@ -46,6 +75,23 @@ pub struct TransformGizmoEvent {
     pub from: GlobalTransform,
     pub to: GlobalTransform,
     pub interaction: TransformGizmoInteraction,
+    /// Scale factor applied to the selection during the drag, in gizmo space. This is
+    /// `Vec3::ONE` for interactions that don't scale.
+    pub scale: Vec3,
+}
+
+/// Sent instead of a [`TransformGizmoEvent`] when a drag is cancelled with `Escape` or a right
+/// click. The selection has already been restored to the transforms it had before the drag.
+#[derive(Debug, Clone, Event)]
+pub struct TransformGizmoCancelEvent {
+    pub interaction: TransformGizmoInteraction,
+}
+
+/// Sent instead of a [`TransformGizmoEvent`] when a handle is pressed and released without the
+/// cursor moving past [`GizmoSettings::drag_threshold`].
+#[derive(Debug, Clone, Event)]
+pub struct TransformGizmoClickEvent {
+    pub interaction: TransformGizmoInteraction,
 }
 
 #[derive(Component, Default, Clone, Debug)]
@@ -60,9 +106,83 @@ pub struct GizmoSettings {
     /// Rotation to apply to the gizmo when it is placed. Used to align the gizmo to a different
     /// coordinate system.
     pub alignment_rotation: Quat,
+    /// Coordinate space the gizmo's axes are aligned to.
+    pub space: GizmoSpace,
+    /// Point that the gizmo is placed at, and that rotation and scale are applied around.
+    pub pivot: PivotMode,
     pub allow_rotation: bool,
+    /// Whether dragging inside the rotation arcs, away from any handle, rotates the selection
+    /// freely like a trackball.
+    pub allow_free_rotation: bool,
+    pub allow_scale: bool,
+    pub snapping: GizmoSnapping,
+    /// Holding any of these keys scales down cursor motion while dragging, for finer control.
+    pub precision_keys: Vec<KeyCode>,
+    /// Factor applied to cursor motion while a precision key is held.
+    pub precision_factor: f32,
+    /// Keys that start a translate, rotate, or scale without grabbing a handle.
+    pub modal_keys: ModalKeys,
+    /// Distance, in logical pixels, that the cursor must move after pressing a handle before the
+    /// drag starts. Releasing the handle before that sends a [`TransformGizmoClickEvent`].
+    pub drag_threshold: f32,
+    /// System that is given the new transforms of the selection, instead of the gizmo writing them
+    /// to each entity's [`Transform`]. This applies to drags, cancelled drags, and undo or redo.
+    /// [`GizmoHistory`] only records edits that end up changing [`Transform`].
+    pub transform_hook: Option<SystemId<Vec<TransformTarget>>>,
+    /// System that checks each transform proposed during a drag before it is applied.
+    pub transform_validator: Option<SystemId<TransformTarget, TransformValidation>>,
+}
+
+/// A transform the gizmo would apply to an entity, given to [`GizmoSettings::transform_hook`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransformTarget {
+    pub entity: Entity,
+    /// New transform relative to the entity's parent, as it would be written to [`Transform`].
+    pub local: Transform,
+    /// New transform in world space.
+    pub global: Transform,
+}
+
+/// Coordinate space that the gizmo handles are oriented in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GizmoSpace {
+    /// Aligned to the world axes, rotated by [`GizmoSettings::alignment_rotation`].
+    #[default]
+    World,
+    /// Aligned to the rotation of the [`ActiveSelection`].
+    Local,
+    /// Aligned to the rotation of the [`ActiveSelection`]'s parent. Entities without a parent fall
+    /// back to world space.
+    Parent,
+}
+
+/// Point around which the selection is rotated and scaled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PivotMode {
+    /// Mean of the selected entities' origins.
+    MedianPoint,
+    /// Center of the bounding box enclosing the selected entities' [`Aabb`]s.
+    BoundingBoxCenter,
+    /// Each entity rotates and scales around its own origin, offset by its
+    /// [`RotationOriginOffset`]. The gizmo is placed at the median point.
+    #[default]
+    IndividualOrigins,
+    /// Origin of the [`ActiveSelection`].
+    ActiveElement,
+    /// Position of the [`PivotCursor`].
+    Cursor,
 }
 
+/// A user placed point in world space, used as the pivot in [`PivotMode::Cursor`].
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PivotCursor(pub Vec3);
+
+/// The selected entity used to orient the gizmo in [`GizmoSpace::Local`] and
+/// [`GizmoSpace::Parent`]. This is updated to the most recently selected entity, but can also be
+/// set manually.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ActiveSelection(pub Option<Entity>);
+
 #[derive(Default, Debug, Clone)]
 pub struct TransformGizmoPlugin {
     // Rotation to apply to the gizmo when it is placed. Used to align the gizmo to a different
@@ -89,15 +209,37 @@ impl Plugin for TransformGizmoPlugin {
         app.insert_resource(GizmoSettings {
             enabled: true,
             alignment_rotation,
+            space: GizmoSpace::default(),
+            pivot: PivotMode::default(),
             allow_rotation: true,
+            allow_free_rotation: false,
+            allow_scale: true,
+            snapping: GizmoSnapping::default(),
+            precision_keys: vec![KeyCode::ShiftLeft, KeyCode::ShiftRight],
+            precision_factor: 0.1,
+            modal_keys: ModalKeys::default(),
+            drag_threshold: 3.0,
+            transform_hook: None,
+            transform_validator: None,
         })
         .insert_resource(GizmoSystemsEnabled(true))
+        .init_resource::<ActiveSelection>()
+        .init_resource::<PivotCursor>()
+        .init_resource::<GizmoHistory>()
+        .init_resource::<GizmoStyle>()
+        .init_resource::<ProposedTransforms>()
         .add_plugins((
             MaterialPlugin::<GizmoMaterial>::default(),
             picking::GizmoPickingPlugin,
             Ui3dNormalization,
         ))
-        .add_event::<TransformGizmoEvent>();
+        .add_event::<TransformGizmoEvent>()
+        .add_event::<TransformGizmoCancelEvent>()
+        .add_event::<TransformGizmoClickEvent>()
+        .add_event::<GizmoDragStarted>()
+        .add_event::<GizmoDragUpdated>()
+        .add_event::<GizmoDragEnded>()
+        .add_event::<GizmoDragCancelled>();
 
         // Input Set
         app.add_systems(
@@ -108,9 +250,15 @@ impl Plugin for TransformGizmoPlugin {
                     .in_set(TransformGizmoSystem::Hover)
                     .in_set(PickSet::Backend)
                     .after(RaycastSystem::UpdateRaycast::<GizmoRaycastSet>),
+                modal::modal_transform
+                    .in_set(TransformGizmoSystem::Modal)
+                    .after(PickSet::Focus),
+                numeric::numeric_entry.in_set(TransformGizmoSystem::NumericEntry),
                 grab_gizmo
                     .in_set(TransformGizmoSystem::Grab)
                     .after(PickSet::Focus),
+                cancel_gizmo.in_set(TransformGizmoSystem::Cancel),
+                history::apply_gizmo_history.in_set(TransformGizmoSystem::History),
             )
                 .chain()
                 .in_set(TransformGizmoSystem::InputsSet)
@@ -121,14 +269,21 @@ impl Plugin for TransformGizmoPlugin {
         app.add_systems(
             PostUpdate,
             (
+                update_active_selection,
                 drag_gizmo
                     .in_set(TransformGizmoSystem::Drag)
                     .before(TransformSystem::TransformPropagate),
+                validation::apply_proposed_transforms
+                    .in_set(TransformGizmoSystem::Drag)
+                    .before(TransformSystem::TransformPropagate),
                 place_gizmo
                     .in_set(TransformGizmoSystem::Place)
                     .after(TransformSystem::TransformPropagate),
+                drag_events::send_drag_events,
                 propagate_gizmo_elements,
-                adjust_view_translate_gizmo.in_set(TransformGizmoSystem::Drag),
+                adjust_view_aligned_gizmos.in_set(TransformGizmoSystem::Drag),
+                update_rotation_sweep.in_set(TransformGizmoSystem::Drag),
+                gizmo_material::update_handle_materials.in_set(TransformGizmoSystem::Drag),
                 gizmo_cam_copy_settings.in_set(TransformGizmoSystem::Drag),
             )
                 .chain()
@@ -136,6 +291,13 @@ impl Plugin for TransformGizmoPlugin {
                 .run_if(|settings: Res<GizmoSettings>| settings.enabled),
         );
 
+        app.add_systems(
+            PostUpdate,
+            mesh::rebuild_gizmo
+                .before(TransformGizmoSystem::MainSet)
+                .run_if(resource_changed::<GizmoStyle>),
+        );
+
         app.add_systems(Startup, mesh::build_gizmo)
             .add_systems(PostStartup, place_gizmo);
     }
@@ -165,7 +327,7 @@ impl Default for TransformGizmoBundle {
             view_visibility: ViewVisibility::default(),
             gizmo: TransformGizmo::default(),
             global_transform: GlobalTransform::default(),
-            normalize: Normalize3d::new(1.5, 150.0),
+            normalize: GizmoStyle::default().normalize(),
         }
     }
 }
@@ -177,8 +339,37 @@ pub struct TransformGizmo {
     // much total dragging has occurred without accumulating error across frames.
     drag_start: Option<Vec3>,
     origin_drag_start: Option<Vec3>,
+    // Cursor position where the handle was grabbed, until the cursor moves past the drag threshold.
+    press_cursor: Option<Vec2>,
+    // Set when a handle is grabbed with the left mouse button, until the button is released.
+    // `PickingInteraction::Pressed` is also set by the other mouse buttons, so it can't be used to
+    // tell whether a handle is being dragged.
+    grabbed: bool,
     // Initial transform of the gizmo
     initial_transform: Option<GlobalTransform>,
+    // Translation, rotation, and scale applied by the current drag, in the space of the gizmo when
+    // the drag started.
+    delta: Transform,
+    // Set when the current drag has been cancelled or committed before the mouse button was
+    // released, until it is released.
+    drag_ended: bool,
+    // Real and adjusted cursor positions when the precision key was last pressed or released.
+    precision_anchor: Option<(Vec2, Vec2)>,
+    precision_active: bool,
+    // Rotation accumulated over the course of a free rotation drag.
+    free_rotation: Quat,
+    // Cursor direction from the previous frame of an axis rotation drag, and the total angle swept
+    // since the drag started. Accumulating frame by frame allows rotating past 180 degrees.
+    last_rotation_vector: Option<Vec3>,
+    rotation_angle: f32,
+    // Angle actually applied to the selection, after snapping.
+    applied_rotation_angle: f32,
+    // Set while a modal transform started from the keyboard is in progress.
+    modal: Option<ModalTransform>,
+    // Value typed during the drag, which replaces the cursor-derived delta.
+    numeric_input: Option<NumericInput>,
+    // Set while the transforms proposed by the drag are rejected by the validator.
+    rejected: bool,
 }
 
 impl TransformGizmo {
@@ -186,41 +377,127 @@ impl TransformGizmo {
     pub fn current_interaction(&self) -> Option<TransformGizmoInteraction> {
         self.current_interaction
     }
+
+    /// Get the mode of the modal transform in progress, if any.
+    pub fn modal_mode(&self) -> Option<ModalMode> {
+        self.modal.map(|modal| modal.mode())
+    }
+
+    /// Get the value typed on the keyboard during the current drag, if any.
+    pub fn numeric_input(&self) -> Option<&str> {
+        self.numeric_input.as_ref().map(NumericInput::text)
+    }
+
+    /// Returns `true` if the gizmo is being dragged, or a modal transform is in progress.
+    fn is_active(&self) -> bool {
+        (self.grabbed || self.modal.is_some()) && !self.drag_ended
+    }
+
+    /// Returns the cursor position to drag with, where cursor motion is scaled by
+    /// `precision_factor` while `precision` is `true`. Motion is measured from where the cursor
+    /// was when `precision` last changed, so the selection doesn't jump when toggling it.
+    fn precision_cursor(&mut self, cursor: Vec2, precision: bool, precision_factor: f32) -> Vec2 {
+        let (anchor_cursor, anchor_adjusted) =
+            *self.precision_anchor.get_or_insert((cursor, cursor));
+        let factor = if self.precision_active {
+            precision_factor
+        } else {
+            1.0
+        };
+        let adjusted = anchor_adjusted + (cursor - anchor_cursor) * factor;
+        if precision != self.precision_active {
+            self.precision_anchor = Some((cursor, adjusted));
+            self.precision_active = precision;
+        }
+        adjusted
+    }
 }
 
 /// Marks the current active gizmo interaction
 #[derive(Clone, Copy, Debug, PartialEq, Component)]
 pub enum TransformGizmoInteraction {
-    TranslateAxis { original: Vec3, axis: Vec3 },
-    TranslatePlane { original: Vec3, normal: Vec3 },
-    RotateAxis { original: Vec3, axis: Vec3 },
-    ScaleAxis { original: Vec3, axis: Vec3 },
+    TranslateAxis {
+        original: Vec3,
+        axis: Vec3,
+    },
+    TranslatePlane {
+        original: Vec3,
+        normal: Vec3,
+    },
+    RotateAxis {
+        original: Vec3,
+        axis: Vec3,
+    },
+    /// Rotates freely about axes in the camera's view plane, following the cursor like a
+    /// trackball.
+    RotateFree,
+    ScaleAxis {
+        original: Vec3,
+        axis: Vec3,
+    },
+    /// Scales the two axes lying in the plane with the given normal, leaving the normal axis
+    /// unchanged.
+    ScalePlane {
+        original: Vec3,
+        normal: Vec3,
+    },
+    /// Scales all three axes together, based on the cursor's distance from the gizmo origin.
+    ScaleUniform,
+    /// Moves the selection so the gizmo origin follows the cursor over the surface of pickable
+    /// meshes. Started by grabbing the view translate handle while holding
+    /// [`GizmoSnapping::surface_keys`].
+    TranslateSurface,
 }
 
 #[derive(Component)]
 struct InitialTransform {
     transform: Transform,
+    // Local transform, used to restore the entity exactly if the drag is cancelled.
+    local_transform: Transform,
     rotation_offset: Vec3,
 }
 
+impl InitialTransform {
+    /// Returns how the local transform of `entity` has changed since the drag started.
+    fn change(&self, entity: Entity, transform: &Transform) -> TransformChange {
+        TransformChange {
+            entity,
+            from: self.local_transform,
+            to: *transform,
+        }
+    }
+}
+
+/// Smallest scale factor a scale interaction can apply, to avoid collapsing or inverting entities.
+const MIN_SCALE_FACTOR: f32 = 0.01;
+
 /// Updates the position of the gizmo and selected meshes while the gizmo is being dragged.
-#[allow(clippy::type_complexity)]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn drag_gizmo(
-    pick_cam: Query<&GizmoPickSource>,
+    plugin_settings: Res<GizmoSettings>,
+    style: Res<GizmoStyle>,
+    keys: Res<ButtonInput<KeyCode>>,
+    pick_cam: Query<(&GizmoPickSource, &Camera, &GlobalTransform)>,
     mut gizmo_mut: Query<&mut TransformGizmo>,
-    mut transform_query: Query<
-        (
-            &PickSelection,
-            Option<&Parent>,
-            &mut Transform,
-            &InitialTransform,
-        ),
-        Without<TransformGizmo>,
+    mut proposed_transforms: ResMut<ProposedTransforms>,
+    constraints_query: Query<(&GizmoConstraints, &InitialTransform)>,
+    transform_query: Query<
+        (Entity, &PickSelection, Option<&Parent>, &InitialTransform),
+        (Without<TransformGizmo>, Without<GizmoLocked>),
     >,
     parent_query: Query<&GlobalTransform>,
-    gizmo_query: Query<(&GlobalTransform, &PickingInteraction), With<TransformGizmo>>,
+    gizmo_query: Query<&GlobalTransform, With<TransformGizmo>>,
+    mut raycast: Raycast,
+    surface_query: Query<(), With<Pickable>>,
+    dragged_query: Query<(Option<&Parent>, Has<InitialTransform>)>,
+    meshes: Res<Assets<Mesh>>,
+    vertex_target_query: Query<
+        (&Handle<Mesh>, &GlobalTransform),
+        (With<GizmoTransformable>, Without<InitialTransform>),
+    >,
+    vertex_source_query: Query<(&Handle<Mesh>, &InitialTransform)>,
 ) {
-    let picking_camera = if let Some(cam) = pick_cam.iter().last() {
+    let (picking_camera, camera, camera_transform) = if let Some(cam) = pick_cam.iter().last() {
         cam
     } else {
         // Not exactly one picking camera.
@@ -236,18 +513,46 @@ fn drag_gizmo(
     // should have no effect on the handle. We can do this by projecting the vector from the handle
     // click point to mouse's current position, onto the axis of the direction we are dragging. See
     // the wiki article for details: https://en.wikipedia.org/wiki/Vector_projection
-    let gizmo_transform =
-        if let Ok((transform, &PickingInteraction::Pressed)) = gizmo_query.get_single() {
-            transform.to_owned()
-        } else {
-            return;
-        };
     let mut gizmo = if let Ok(g) = gizmo_mut.get_single_mut() {
         g
     } else {
         error!("Number of transform gizmos is != 1");
         return;
     };
+    let gizmo_transform = match gizmo_query.get_single() {
+        Ok(transform) if gizmo.is_active() => transform.to_owned(),
+        _ => return,
+    };
+    // While the precision key is held, build the ray from a cursor position that moves slower than
+    // the real cursor.
+    let picking_ray = match picking_camera.cast_method {
+        RaycastMethod::Screenspace(cursor) => {
+            // Wait for the cursor to move past the threshold, then start the drag from where the
+            // handle was pressed so the selection doesn't lag behind the cursor.
+            let cursor = match gizmo.press_cursor {
+                Some(press_cursor) => {
+                    if cursor.distance(press_cursor) < plugin_settings.drag_threshold {
+                        return;
+                    }
+                    gizmo.press_cursor = None;
+                    press_cursor
+                }
+                None => cursor,
+            };
+            let precision = keys.any_pressed(plugin_settings.precision_keys.iter().copied());
+            let cursor =
+                gizmo.precision_cursor(cursor, precision, plugin_settings.precision_factor);
+            let viewport_origin = camera
+                .logical_viewport_rect()
+                .map(|rect| rect.min)
+                .unwrap_or(Vec2::ZERO);
+            match camera.viewport_to_world(camera_transform, cursor - viewport_origin) {
+                Some(ray) => ray,
+                None => return,
+            }
+        }
+        _ => picking_ray,
+    };
     let gizmo_origin = match gizmo.origin_drag_start {
         Some(origin) => origin,
         None => {
@@ -257,9 +562,9 @@ fn drag_gizmo(
         }
     };
     let selected_iter = transform_query
-        .iter_mut()
-        .filter(|(s, ..)| s.is_selected)
-        .map(|(_, parent, local_transform, initial_global_transform)| {
+        .iter()
+        .filter(|(_, s, ..)| s.is_selected)
+        .map(|(entity, _, parent, initial_global_transform)| {
             let parent_global_transform = match parent {
                 Some(parent) => match parent_query.get(parent.get()) {
                     Ok(transform) => *transform,
@@ -269,20 +574,105 @@ fn drag_gizmo(
             };
             let parent_mat = parent_global_transform.compute_matrix();
             let inverse_parent = parent_mat.inverse();
-            (inverse_parent, local_transform, initial_global_transform)
+            (entity, inverse_parent, initial_global_transform)
         });
+    // New transforms are validated and applied by `apply_proposed_transforms`.
+    let mut apply_transform = |entity: Entity, inverse_parent: Mat4, global: Transform| {
+        let global = match constraints_query.get(entity) {
+            Ok((constraints, initial_transform)) => {
+                constraints.constrain(initial_transform, inverse_parent, global)
+            }
+            Err(_) => global,
+        };
+        proposed_transforms.0.push(TransformTarget {
+            entity,
+            local: Transform::from_matrix(inverse_parent * global.compute_matrix()),
+            global,
+        });
+    };
+    let gizmo_rotation = gizmo_transform.compute_transform().rotation;
+    let snapping = plugin_settings.snapping.is_active(&keys);
+    let vertex_snapping = plugin_settings
+        .snapping
+        .vertex_key
+        .is_some_and(|key| keys.pressed(key));
+    let mut vertex_snap_translation = || {
+        // Children of the selection move with it, so they can't be snapped to either.
+        let filter =
+            |entity| vertex_target_query.contains(entity) && !is_dragged(entity, &dragged_query);
+        let settings = RaycastSettings::default().with_filter(&filter);
+        let (target_entity, hit) = raycast.cast_ray(picking_ray, &settings).first()?;
+        let (mesh, transform) = vertex_target_query.get(*target_entity).ok()?;
+        let target = snapping::nearest_vertex(
+            meshes.get(mesh)?,
+            &transform.compute_matrix(),
+            hit.position(),
+        )?;
+        let source = match plugin_settings.snapping.vertex_source {
+            VertexSnapSource::Pivot => gizmo_origin,
+            VertexSnapSource::NearestVertex => vertex_source_query
+                .iter()
+                .filter_map(|(mesh, initial_transform)| {
+                    snapping::nearest_vertex(
+                        meshes.get(mesh)?,
+                        &initial_transform.transform.compute_matrix(),
+                        target,
+                    )
+                })
+                .min_by(|a, b| {
+                    a.distance_squared(target)
+                        .total_cmp(&b.distance_squared(target))
+                })?,
+        };
+        Some(target - source)
+    };
+    // Rotation and scale are applied around the gizmo, unless every entity uses its own origin.
+    let pivot = match plugin_settings.pivot {
+        PivotMode::IndividualOrigins => None,
+        _ => Some(gizmo_origin),
+    };
+    let rotate_around_pivot = |initial_transform: &InitialTransform, rotation: Quat| {
+        let translation = match pivot {
+            Some(pivot) => pivot + rotation * (initial_transform.transform.translation - pivot),
+            None => {
+                let world_space_offset =
+                    initial_transform.transform.rotation * initial_transform.rotation_offset;
+                let offset_rotated = rotation * world_space_offset;
+                let offset = world_space_offset - offset_rotated;
+                initial_transform.transform.translation + offset
+            }
+        };
+        Transform {
+            translation,
+            rotation: rotation * initial_transform.transform.rotation,
+            scale: initial_transform.transform.scale,
+        }
+    };
+    let scale_around_pivot = |translation: Vec3, gizmo_space_scale: Vec3| match pivot {
+        Some(pivot) => {
+            let offset = gizmo_rotation.inverse() * (translation - pivot);
+            pivot + gizmo_rotation * (offset * gizmo_space_scale)
+        }
+        None => translation,
+    };
+    // A typed value overrides the distance, angle, or scale factor derived from the cursor.
+    let numeric_value = gizmo.numeric_input.as_ref().and_then(NumericInput::value);
     if let Some(interaction) = gizmo.current_interaction {
         if gizmo.initial_transform.is_none() {
             gizmo.initial_transform = Some(gizmo_transform);
         }
+        let initial_gizmo_rotation = gizmo.initial_transform.map_or(gizmo_rotation, |transform| {
+            transform.compute_transform().rotation
+        });
+        let rotation_to_gizmo_space =
+            |rotation: Quat| initial_gizmo_rotation.inverse() * rotation * initial_gizmo_rotation;
         match interaction {
-            TransformGizmoInteraction::TranslateAxis { original: _, axis } => {
+            TransformGizmoInteraction::TranslateAxis { axis, .. } => {
                 let vertical_vector = picking_ray.direction.cross(axis).normalize();
                 let plane_normal = axis.cross(vertical_vector).normalize();
                 let plane_origin = gizmo_origin;
-                let cursor_plane_intersection = if let Some(intesect_point) = picking_camera
-                    .get_ray()
-                    .and_then(|ray| intersect_plane(ray, plane_normal, plane_origin))
+                let cursor_plane_intersection = if let Some(intesect_point) =
+                    intersect_plane(picking_ray, plane_normal, plane_origin)
                 {
                     intesect_point
                 } else {
@@ -304,25 +694,36 @@ fn drag_gizmo(
                 let selected_handle_vec = cursor_projected_onto_handle - plane_origin;
                 let new_handle_vec = cursor_vector.dot(selected_handle_vec.normalize())
                     * selected_handle_vec.normalize();
-                let translation = new_handle_vec - selected_handle_vec;
-                selected_iter.for_each(
-                    |(inverse_parent, mut local_transform, initial_global_transform)| {
-                        let new_transform = Transform {
-                            translation: initial_global_transform.transform.translation
-                                + translation,
-                            rotation: initial_global_transform.transform.rotation,
-                            scale: initial_global_transform.transform.scale,
-                        };
-                        let local = inverse_parent * new_transform.compute_matrix();
-                        local_transform.set_if_neq(Transform::from_matrix(local));
-                    },
-                );
+                let mut translation = new_handle_vec - selected_handle_vec;
+                if let Some(distance) = numeric_value {
+                    translation = axis.normalize() * distance;
+                } else if let Some(vertex_translation) =
+                    vertex_snapping.then(&mut vertex_snap_translation).flatten()
+                {
+                    translation = vertex_translation;
+                } else if snapping {
+                    // Modal transforms can be constrained to an axis other than the gizmo's.
+                    translation = plugin_settings.snapping.snap_translation_along(
+                        gizmo_origin,
+                        translation,
+                        axis,
+                    );
+                }
+                gizmo.delta =
+                    Transform::from_translation(initial_gizmo_rotation.inverse() * translation);
+                selected_iter.for_each(|(entity, inverse_parent, initial_global_transform)| {
+                    let new_transform = Transform {
+                        translation: initial_global_transform.transform.translation + translation,
+                        rotation: initial_global_transform.transform.rotation,
+                        scale: initial_global_transform.transform.scale,
+                    };
+                    apply_transform(entity, inverse_parent, new_transform);
+                });
             }
-            TransformGizmoInteraction::TranslatePlane { normal, .. } => {
+            TransformGizmoInteraction::TranslatePlane { original, normal } => {
                 let plane_origin = gizmo_origin;
-                let cursor_plane_intersection = if let Some(intersection) = picking_camera
-                    .get_ray()
-                    .and_then(|ray| intersect_plane(ray, normal, plane_origin))
+                let cursor_plane_intersection = if let Some(intersection) =
+                    intersect_plane(picking_ray, normal, plane_origin)
                 {
                     intersection
                 } else {
@@ -335,63 +736,300 @@ fn drag_gizmo(
                         return;
                     }
                 };
-                selected_iter.for_each(
-                    |(inverse_parent, mut local_transform, initial_transform)| {
-                        let new_transform = Transform {
-                            translation: initial_transform.transform.translation
-                                + cursor_plane_intersection
-                                - drag_start,
-                            rotation: initial_transform.transform.rotation,
-                            scale: initial_transform.transform.scale,
-                        };
-                        let local = inverse_parent * new_transform.compute_matrix();
-                        local_transform.set_if_neq(Transform::from_matrix(local));
-                    },
-                );
+                let mut translation = cursor_plane_intersection - drag_start;
+                if let Some(vertex_translation) =
+                    vertex_snapping.then(&mut vertex_snap_translation).flatten()
+                {
+                    translation = vertex_translation;
+                } else if snapping {
+                    // Modal transforms can be constrained to a plane other than the gizmo's, so
+                    // the gizmo's axes are turned to match, keeping the translation in the plane.
+                    // The view plane snaps on the gizmo's axes.
+                    let rotation = if original == Vec3::ZERO {
+                        gizmo_rotation
+                    } else {
+                        Quat::from_rotation_arc(
+                            (gizmo_rotation * original).normalize(),
+                            normal.normalize(),
+                        ) * gizmo_rotation
+                    };
+                    translation = plugin_settings.snapping.snap_translation(
+                        gizmo_origin,
+                        translation,
+                        rotation,
+                        Vec3::ONE - original.abs(),
+                    );
+                }
+                gizmo.delta =
+                    Transform::from_translation(initial_gizmo_rotation.inverse() * translation);
+                selected_iter.for_each(|(entity, inverse_parent, initial_transform)| {
+                    let new_transform = Transform {
+                        translation: initial_transform.transform.translation + translation,
+                        rotation: initial_transform.transform.rotation,
+                        scale: initial_transform.transform.scale,
+                    };
+                    apply_transform(entity, inverse_parent, new_transform);
+                });
+            }
+            TransformGizmoInteraction::TranslateSurface => {
+                // Only hit meshes that aren't being moved, including the children of the
+                // selection, otherwise the selection would keep snapping onto itself.
+                let filter =
+                    |entity| surface_query.contains(entity) && !is_dragged(entity, &dragged_query);
+                let settings = RaycastSettings::default().with_filter(&filter);
+                let (hit_position, hit_normal) =
+                    match raycast.cast_ray(picking_ray, &settings).first() {
+                        Some((_, hit)) => (hit.position(), hit.normal()),
+                        None => return,
+                    };
+                let rotation = if plugin_settings.snapping.align_to_surface {
+                    let initial_up = gizmo
+                        .initial_transform
+                        .map(|transform| *transform.up())
+                        .unwrap_or(Vec3::Y);
+                    Quat::from_rotation_arc(initial_up, hit_normal.normalize())
+                } else {
+                    Quat::IDENTITY
+                };
+                gizmo.delta = Transform {
+                    translation: initial_gizmo_rotation.inverse() * (hit_position - gizmo_origin),
+                    rotation: rotation_to_gizmo_space(rotation),
+                    scale: Vec3::ONE,
+                };
+                selected_iter.for_each(|(entity, inverse_parent, initial_transform)| {
+                    let new_transform = Transform {
+                        translation: hit_position
+                            + rotation * (initial_transform.transform.translation - gizmo_origin),
+                        rotation: rotation * initial_transform.transform.rotation,
+                        scale: initial_transform.transform.scale,
+                    };
+                    apply_transform(entity, inverse_parent, new_transform);
+                });
             }
             TransformGizmoInteraction::RotateAxis { original: _, axis } => {
-                let cursor_plane_intersection = if let Some(intersection) = picking_camera
-                    .get_ray()
-                    .and_then(|ray| intersect_plane(ray, axis.normalize(), gizmo_origin))
+                let cursor_plane_intersection = if let Some(intersection) =
+                    intersect_plane(picking_ray, axis.normalize(), gizmo_origin)
                 {
                     intersection
                 } else {
                     return;
                 };
-                let cursor_vector = (cursor_plane_intersection - gizmo_origin).normalize();
+                let cursor_vector = (cursor_plane_intersection - gizmo_origin).normalize_or_zero();
+                if cursor_vector == Vec3::ZERO {
+                    return;
+                }
                 let drag_start = match &gizmo.drag_start {
                     Some(drag_start) => *drag_start,
                     None => {
                         gizmo.drag_start = Some(cursor_vector);
+                        gizmo.last_rotation_vector = Some(cursor_vector);
                         return; // We just started dragging, no transformation is needed yet, exit early.
                     }
                 };
-                let dot = drag_start.dot(cursor_vector);
-                let det = axis.dot(drag_start.cross(cursor_vector));
-                let angle = det.atan2(dot);
+                let last_vector = gizmo.last_rotation_vector.unwrap_or(drag_start);
+                let dot = last_vector.dot(cursor_vector);
+                let det = axis.dot(last_vector.cross(cursor_vector));
+                gizmo.rotation_angle += det.atan2(dot);
+                gizmo.last_rotation_vector = Some(cursor_vector);
+                let mut angle = gizmo.rotation_angle;
+                if let Some(degrees) = numeric_value {
+                    angle = degrees.to_radians();
+                } else if snapping {
+                    angle = plugin_settings.snapping.snap_angle(angle);
+                }
+                gizmo.applied_rotation_angle = angle;
                 let rotation = Quat::from_axis_angle(axis, angle);
-                selected_iter.for_each(
-                    |(inverse_parent, mut local_transform, initial_transform)| {
-                        let world_space_offset = initial_transform.transform.rotation
-                            * initial_transform.rotation_offset;
-                        let offset_rotated = rotation * world_space_offset;
-                        let offset = world_space_offset - offset_rotated;
-                        let new_transform = Transform {
-                            translation: initial_transform.transform.translation + offset,
-                            rotation: rotation * initial_transform.transform.rotation,
-                            scale: initial_transform.transform.scale,
-                        };
-                        let local = inverse_parent * new_transform.compute_matrix();
-                        local_transform.set_if_neq(Transform::from_matrix(local));
-                    },
-                );
+                gizmo.delta = Transform::from_rotation(rotation_to_gizmo_space(rotation));
+                selected_iter.for_each(|(entity, inverse_parent, initial_transform)| {
+                    let new_transform = rotate_around_pivot(initial_transform, rotation);
+                    apply_transform(entity, inverse_parent, new_transform);
+                });
             }
-            TransformGizmoInteraction::ScaleAxis {
-                original: _,
-                axis: _,
-            } => (),
+            TransformGizmoInteraction::RotateFree => {
+                let plane_normal = *camera_transform.back();
+                let cursor_plane_intersection = if let Some(intersection) =
+                    intersect_plane(picking_ray, plane_normal, gizmo_origin)
+                {
+                    intersection
+                } else {
+                    return;
+                };
+                // Trackball rotation is incremental, so `drag_start` holds the cursor position from
+                // the previous frame, and the rotation is accumulated in `free_rotation`.
+                let last_cursor = match gizmo.drag_start {
+                    Some(last_cursor) => last_cursor,
+                    None => {
+                        gizmo.drag_start = Some(cursor_plane_intersection);
+                        return;
+                    }
+                };
+                gizmo.drag_start = Some(cursor_plane_intersection);
+                let cursor_delta = cursor_plane_intersection - last_cursor;
+                // Moving the cursor across the radius of the arcs rotates by one radian.
+                let radius = style.arc_radius * gizmo_transform.compute_transform().scale.x;
+                if cursor_delta.length() > f32::EPSILON && radius > f32::EPSILON {
+                    let axis = plane_normal.cross(cursor_delta).normalize();
+                    let delta_rotation =
+                        Quat::from_axis_angle(axis, cursor_delta.length() / radius);
+                    gizmo.free_rotation = (delta_rotation * gizmo.free_rotation).normalize();
+                }
+                let rotation = gizmo.free_rotation;
+                gizmo.delta = Transform::from_rotation(rotation_to_gizmo_space(rotation));
+                selected_iter.for_each(|(entity, inverse_parent, initial_transform)| {
+                    let new_transform = rotate_around_pivot(initial_transform, rotation);
+                    apply_transform(entity, inverse_parent, new_transform);
+                });
+            }
+            TransformGizmoInteraction::ScaleAxis { original, axis } => {
+                let axis = axis.normalize();
+                let vertical_vector = picking_ray.direction.cross(axis).normalize();
+                let plane_normal = axis.cross(vertical_vector).normalize();
+                let plane_origin = gizmo_origin;
+                let cursor_plane_intersection = if let Some(intersection) =
+                    intersect_plane(picking_ray, plane_normal, plane_origin)
+                {
+                    intersection
+                } else {
+                    return;
+                };
+                let cursor_vector = cursor_plane_intersection - plane_origin;
+                let drag_start = match &gizmo.drag_start {
+                    Some(drag_start) => *drag_start,
+                    None => {
+                        gizmo.drag_start = Some(cursor_vector.dot(axis) * axis + plane_origin);
+                        return;
+                    }
+                };
+                // The scale factor is the ratio of the cursor's current distance along the axis to
+                // its distance when the drag started. Using the signed distances means this works
+                // regardless of which side of the origin the handle is on.
+                let start_distance = (drag_start - plane_origin).dot(axis);
+                if start_distance.abs() <= f32::EPSILON {
+                    return;
+                }
+                let scale_factor = numeric_value
+                    .unwrap_or(cursor_vector.dot(axis) / start_distance)
+                    .max(MIN_SCALE_FACTOR);
+                let gizmo_space_scale = Vec3::ONE + original.abs() * (scale_factor - 1.0);
+                gizmo.delta = Transform::from_scale(gizmo_space_scale);
+                selected_iter.for_each(|(entity, inverse_parent, initial_transform)| {
+                    // Distribute the scale onto the entity's local axes, so that entities that
+                    // aren't aligned with the gizmo are still scaled along the dragged axis.
+                    let local_axis = (initial_transform.transform.rotation.inverse() * axis).abs();
+                    let new_transform = Transform {
+                        translation: scale_around_pivot(
+                            initial_transform.transform.translation,
+                            gizmo_space_scale,
+                        ),
+                        rotation: initial_transform.transform.rotation,
+                        scale: initial_transform.transform.scale
+                            * (Vec3::ONE + local_axis * (scale_factor - 1.0)),
+                    };
+                    apply_transform(entity, inverse_parent, new_transform);
+                });
+            }
+            TransformGizmoInteraction::ScalePlane { original, normal } => {
+                let normal = normal.normalize();
+                let cursor_plane_intersection = if let Some(intersection) =
+                    intersect_plane(picking_ray, normal, gizmo_origin)
+                {
+                    intersection
+                } else {
+                    return;
+                };
+                let cursor_vector = cursor_plane_intersection - gizmo_origin;
+                let drag_start = match &gizmo.drag_start {
+                    Some(drag_start) => *drag_start,
+                    None => {
+                        gizmo.drag_start = Some(cursor_vector);
+                        return;
+                    }
+                };
+                let start_distance = drag_start.length();
+                if start_distance <= f32::EPSILON {
+                    return;
+                }
+                let scale_factor = numeric_value
+                    .unwrap_or(cursor_vector.dot(drag_start / start_distance) / start_distance)
+                    .max(MIN_SCALE_FACTOR);
+                let gizmo_space_scale =
+                    Vec3::ONE + (Vec3::ONE - original.abs()) * (scale_factor - 1.0);
+                gizmo.delta = Transform::from_scale(gizmo_space_scale);
+                selected_iter.for_each(|(entity, inverse_parent, initial_transform)| {
+                    let local_normal =
+                        (initial_transform.transform.rotation.inverse() * normal).abs();
+                    let new_transform = Transform {
+                        translation: scale_around_pivot(
+                            initial_transform.transform.translation,
+                            gizmo_space_scale,
+                        ),
+                        rotation: initial_transform.transform.rotation,
+                        scale: initial_transform.transform.scale
+                            * (Vec3::ONE + (Vec3::ONE - local_normal) * (scale_factor - 1.0)),
+                    };
+                    apply_transform(entity, inverse_parent, new_transform);
+                });
+            }
+            TransformGizmoInteraction::ScaleUniform => {
+                // Distances measured in the view plane through the gizmo origin are proportional to
+                // distances on screen, so their ratio is the screen space scale factor.
+                let plane_normal = *camera_transform.back();
+                let cursor_plane_intersection = if let Some(intersection) =
+                    intersect_plane(picking_ray, plane_normal, gizmo_origin)
+                {
+                    intersection
+                } else {
+                    return;
+                };
+                let cursor_vector = cursor_plane_intersection - gizmo_origin;
+                let drag_start = match &gizmo.drag_start {
+                    Some(drag_start) => *drag_start,
+                    None => {
+                        gizmo.drag_start = Some(cursor_vector);
+                        return;
+                    }
+                };
+                let start_distance = drag_start.length();
+                if start_distance <= f32::EPSILON {
+                    return;
+                }
+                let scale_factor = numeric_value
+                    .unwrap_or(cursor_vector.length() / start_distance)
+                    .max(MIN_SCALE_FACTOR);
+                let gizmo_space_scale = Vec3::splat(scale_factor);
+                gizmo.delta = Transform::from_scale(gizmo_space_scale);
+                selected_iter.for_each(|(entity, inverse_parent, initial_transform)| {
+                    let new_transform = Transform {
+                        translation: scale_around_pivot(
+                            initial_transform.transform.translation,
+                            gizmo_space_scale,
+                        ),
+                        rotation: initial_transform.transform.rotation,
+                        scale: initial_transform.transform.scale * scale_factor,
+                    };
+                    apply_transform(entity, inverse_parent, new_transform);
+                });
+            }
+        }
+    }
+}
+
+/// Returns `true` if `entity` or one of its ancestors is being moved by the gizmo.
+fn is_dragged(
+    entity: Entity,
+    dragged_query: &Query<(Option<&Parent>, Has<InitialTransform>)>,
+) -> bool {
+    let mut current = Some(entity);
+    while let Some(entity) = current {
+        let Ok((parent, dragged)) = dragged_query.get(entity) else {
+            return false;
+        };
+        if dragged {
+            return true;
         }
+        current = parent.map(Parent::get);
     }
+    false
 }
 
 fn intersect_plane(ray: Ray3d, plane_normal: Vec3, plane_origin: Vec3) -> Option<Vec3> {
@@ -407,19 +1045,31 @@ fn intersect_plane(ray: Ray3d, plane_normal: Vec3, plane_origin: Vec3) -> Option
     }
 }
 
+fn ray_hits_sphere(ray: Ray3d, center: Vec3, radius: f32) -> bool {
+    let to_center = center - ray.origin;
+    let distance_along_ray = to_center.dot(*ray.direction);
+    distance_along_ray > 0.0
+        && (to_center - *ray.direction * distance_along_ray).length_squared() <= radius * radius
+}
+
 fn hover_gizmo(
+    plugin_settings: Res<GizmoSettings>,
+    style: Res<GizmoStyle>,
     gizmo_raycast_source: Query<(Entity, &GizmoPickSource)>,
     mut gizmo_query: Query<(
         Entity,
         &Children,
         &mut TransformGizmo,
         &mut PickingInteraction,
-        &Transform,
+        &GlobalTransform,
+        &Visibility,
     )>,
     hover_query: Query<&TransformGizmoInteraction>,
     mut hits: EventWriter<PointerHits>,
 ) {
-    for (gizmo_entity, children, mut gizmo, mut interaction, _transform) in gizmo_query.iter_mut() {
+    for (gizmo_entity, children, mut gizmo, mut interaction, transform, visibility) in
+        gizmo_query.iter_mut()
+    {
         let Ok((camera, gizmo_raycast_source)) = gizmo_raycast_source.get_single() else {
             warn!("There must be exactly one gizmo raycast source");
             return;
@@ -427,7 +1077,7 @@ fn hover_gizmo(
 
         if let Some((topmost_gizmo_entity, _)) = gizmo_raycast_source.get_nearest_intersection() {
             // Only update the gizmo state if it isn't being clicked (dragged) currently.
-            if *interaction != PickingInteraction::Pressed {
+            if *interaction != PickingInteraction::Pressed && gizmo.modal.is_none() {
                 for child in children
                     .iter()
                     .filter(|entity| **entity == topmost_gizmo_entity)
@@ -438,12 +1088,27 @@ fn hover_gizmo(
                     }
                 }
             }
+        } else if plugin_settings.allow_rotation
+            && plugin_settings.allow_free_rotation
+            && *visibility != Visibility::Hidden
+            && gizmo_raycast_source.get_ray().is_some_and(|ray| {
+                let radius = style.arc_radius * transform.compute_transform().scale.x;
+                ray_hits_sphere(ray, transform.translation(), radius)
+            })
+        {
+            // The cursor is inside the rotation arcs without touching a handle.
+            if *interaction != PickingInteraction::Pressed && gizmo.modal.is_none() {
+                *interaction = PickingInteraction::Hovered;
+                gizmo.current_interaction = Some(TransformGizmoInteraction::RotateFree);
+            }
         } else if *interaction == PickingInteraction::Hovered {
             *interaction = PickingInteraction::None
         }
 
-        if !matches!(*interaction, PickingInteraction::None) {
+        if !matches!(*interaction, PickingInteraction::None) || gizmo.modal.is_some() {
             // Tell picking backend we're hovering the gizmo, so the `NoDeselect` component takes effect.
+            // During a modal transform this also keeps the confirming click from changing the
+            // selection.
             let data = HitData {
                 camera,
                 depth: 0.,
@@ -466,104 +1131,319 @@ pub struct RotationOriginOffset(pub Vec3);
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn grab_gizmo(
     mut commands: Commands,
+    plugin_settings: Res<GizmoSettings>,
+    keys: Res<ButtonInput<KeyCode>>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
+    pick_source: Query<&GizmoPickSource>,
     mut gizmo_events: EventWriter<TransformGizmoEvent>,
+    mut cancel_events: EventWriter<TransformGizmoCancelEvent>,
+    mut click_events: EventWriter<TransformGizmoClickEvent>,
+    mut history: ResMut<GizmoHistory>,
     mut gizmo_query: Query<(
         &mut TransformGizmo,
         &mut PickingInteraction,
         &GlobalTransform,
     )>,
-    selected_items_query: Query<(
+    mut selected_items_query: Query<(
         &PickSelection,
         &GlobalTransform,
+        &mut Transform,
         Entity,
         Option<&RotationOriginOffset>,
+        Option<&InitialTransform>,
     )>,
-    initial_transform_query: Query<Entity, With<InitialTransform>>,
+    locked_query: Query<(), With<GizmoLocked>>,
 ) {
     if mouse_button_input.just_pressed(MouseButton::Left) {
         for (mut gizmo, interaction, _transform) in gizmo_query.iter_mut() {
             if *interaction == PickingInteraction::Pressed {
+                // A drag that was cancelled or committed early only ends when the left mouse
+                // button is released, but make sure a missed release can't block this one.
+                gizmo.drag_ended = false;
+                // The view translate handle is the only handle without an original axis.
+                let surface_snap =
+                    keys.any_pressed(plugin_settings.snapping.surface_keys.iter().copied());
+                if let Some(TransformGizmoInteraction::TranslatePlane { original, .. }) =
+                    gizmo.current_interaction
+                {
+                    if surface_snap && original == Vec3::ZERO {
+                        gizmo.current_interaction =
+                            Some(TransformGizmoInteraction::TranslateSurface);
+                    }
+                }
                 // Dragging has started, store the initial position of all selected meshes
-                for (selection, transform, entity, rotation_origin_offset) in
+                let mut any_selected = false;
+                for (selection, transform, local_transform, entity, rotation_origin_offset, _) in
                     selected_items_query.iter()
                 {
-                    if selection.is_selected {
+                    if selection.is_selected && !locked_query.contains(entity) {
+                        any_selected = true;
                         commands.entity(entity).insert(InitialTransform {
                             transform: transform.compute_transform(),
+                            local_transform: *local_transform,
                             rotation_offset: rotation_origin_offset
                                 .map(|offset| offset.0)
                                 .unwrap_or(Vec3::ZERO),
                         });
                     }
                 }
+                if any_selected {
+                    // The drag threshold applies to every grab, which is only started here.
+                    gizmo.grabbed = true;
+                    if let Some(RaycastMethod::Screenspace(cursor)) = pick_source
+                        .get_single()
+                        .ok()
+                        .map(|source| &source.cast_method)
+                    {
+                        gizmo.press_cursor = Some(*cursor);
+                    }
+                } else {
+                    // Every selected entity is locked, so ignore the press until it is released.
+                    *gizmo = TransformGizmo {
+                        drag_ended: true,
+                        ..Default::default()
+                    };
+                }
             } else {
                 *gizmo = TransformGizmo::default();
-                for entity in initial_transform_query.iter() {
-                    commands.entity(entity).remove::<InitialTransform>();
+                for (.., entity, _, initial_transform) in selected_items_query.iter() {
+                    if initial_transform.is_some() {
+                        commands.entity(entity).remove::<InitialTransform>();
+                    }
                 }
             }
         }
     } else if mouse_button_input.just_released(MouseButton::Left) {
         for (mut gizmo, mut interaction, transform) in gizmo_query.iter_mut() {
             *interaction = PickingInteraction::None;
-            if let (Some(from), Some(interaction)) =
-                (gizmo.initial_transform, gizmo.current_interaction())
-            {
-                let event = TransformGizmoEvent {
-                    from,
-                    to: *transform,
-                    interaction,
-                };
-                //info!("{:?}", event);
-                gizmo_events.send(event);
+            if gizmo.initial_transform.is_some() {
+                end_drag(
+                    &mut commands,
+                    plugin_settings.transform_hook,
+                    &mut history,
+                    &mut gizmo_events,
+                    &mut cancel_events,
+                    &gizmo,
+                    transform,
+                    selected_items_query.iter_mut().filter_map(
+                        |(_, _, transform, entity, _, initial_transform)| {
+                            Some((entity, transform, initial_transform?))
+                        },
+                    ),
+                );
+                *gizmo = TransformGizmo::default();
+            } else if let (true, Some(_), Some(interaction)) = (
+                gizmo.grabbed,
+                gizmo.press_cursor,
+                gizmo.current_interaction(),
+            ) {
+                // The cursor never moved far enough to start dragging.
+                click_events.send(TransformGizmoClickEvent { interaction });
                 *gizmo = TransformGizmo::default();
+            } else if gizmo.grabbed || gizmo.drag_ended {
+                *gizmo = TransformGizmo::default();
+            }
+        }
+        for (.., entity, _, initial_transform) in selected_items_query.iter() {
+            if initial_transform.is_some() {
+                commands.entity(entity).remove::<InitialTransform>();
             }
         }
     }
 }
 
+/// Cancels the current drag or modal transform when `Escape` or the right mouse button is pressed,
+/// restoring the selection to its transforms from before the drag.
+fn cancel_gizmo(
+    mut commands: Commands,
+    plugin_settings: Res<GizmoSettings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    mut cancel_events: EventWriter<TransformGizmoCancelEvent>,
+    mut gizmo_query: Query<(&mut TransformGizmo, &mut PickingInteraction)>,
+    mut initial_transform_query: Query<(Entity, &mut Transform, &InitialTransform)>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) && !mouse_button_input.just_pressed(MouseButton::Right) {
+        return;
+    }
+    for (mut gizmo, mut interaction) in gizmo_query.iter_mut() {
+        // Handles that are pressed but haven't moved past the drag threshold have nothing to
+        // cancel.
+        if !gizmo.is_active() || (gizmo.initial_transform.is_none() && gizmo.modal.is_none()) {
+            continue;
+        }
+        let Some(current_interaction) = gizmo.current_interaction else {
+            continue;
+        };
+        restore_initial_transforms(
+            &mut commands,
+            plugin_settings.transform_hook,
+            initial_transform_query.iter_mut(),
+        );
+        for (entity, ..) in initial_transform_query.iter() {
+            commands.entity(entity).remove::<InitialTransform>();
+        }
+        // A modal transform has no mouse button to wait for, so it can be reset right away.
+        *gizmo = TransformGizmo {
+            drag_ended: gizmo.modal.is_none(),
+            ..Default::default()
+        };
+        *interaction = PickingInteraction::None;
+        cancel_events.send(TransformGizmoCancelEvent {
+            interaction: current_interaction,
+        });
+    }
+}
+
+/// Ends the drag of the `dragged` entities, sending a [`TransformGizmoEvent`] and recording the edit
+/// in the [`GizmoHistory`]. If the validator rejected the last transforms proposed by the drag, the
+/// entities are restored instead, as if the drag had been cancelled. The caller removes the
+/// [`InitialTransform`]s.
+#[allow(clippy::too_many_arguments)]
+fn end_drag<'a>(
+    commands: &mut Commands,
+    transform_hook: Option<SystemId<Vec<TransformTarget>>>,
+    history: &mut GizmoHistory,
+    gizmo_events: &mut EventWriter<TransformGizmoEvent>,
+    cancel_events: &mut EventWriter<TransformGizmoCancelEvent>,
+    gizmo: &TransformGizmo,
+    gizmo_transform: &GlobalTransform,
+    dragged: impl Iterator<Item = (Entity, Mut<'a, Transform>, &'a InitialTransform)>,
+) {
+    let (Some(from), Some(interaction)) = (gizmo.initial_transform, gizmo.current_interaction)
+    else {
+        return;
+    };
+    if gizmo.rejected {
+        restore_initial_transforms(commands, transform_hook, dragged);
+        cancel_events.send(TransformGizmoCancelEvent { interaction });
+        return;
+    }
+    history.push(
+        dragged
+            .map(|(entity, transform, initial_transform)| {
+                initial_transform.change(entity, &transform)
+            })
+            .collect(),
+    );
+    gizmo_events.send(TransformGizmoEvent {
+        from,
+        to: *gizmo_transform,
+        interaction,
+        scale: gizmo.delta.scale,
+    });
+}
+
+/// Restores `dragged` entities to their transforms from before the drag, or hands those transforms
+/// to `transform_hook` if there is one.
+fn restore_initial_transforms<'a>(
+    commands: &mut Commands,
+    transform_hook: Option<SystemId<Vec<TransformTarget>>>,
+    dragged: impl Iterator<Item = (Entity, Mut<'a, Transform>, &'a InitialTransform)>,
+) {
+    let Some(transform_hook) = transform_hook else {
+        for (_, mut transform, initial_transform) in dragged {
+            transform.set_if_neq(initial_transform.local_transform);
+        }
+        return;
+    };
+    let targets = dragged
+        .map(|(entity, _, initial_transform)| TransformTarget {
+            entity,
+            local: initial_transform.local_transform,
+            global: initial_transform.transform,
+        })
+        .collect();
+    commands.run_system_with_input(transform_hook, targets);
+}
+
 /// Places the gizmo in space relative to the selected entity(s).
 #[allow(clippy::type_complexity)]
 fn place_gizmo(
     plugin_settings: Res<GizmoSettings>,
+    active_selection: Res<ActiveSelection>,
     mut queries: ParamSet<(
         Query<
             (
                 &PickSelection,
                 &GlobalTransform,
+                Option<&Parent>,
                 Option<&RotationOriginOffset>,
+                Option<&Aabb>,
+                Has<GizmoLocked>,
             ),
             With<GizmoTransformable>,
         >,
         Query<(&mut GlobalTransform, &mut Transform, &mut Visibility), With<TransformGizmo>>,
     )>,
+    parent_query: Query<&GlobalTransform, Without<TransformGizmo>>,
+    pivot_cursor: Res<PivotCursor>,
 ) {
-    let selected: Vec<_> = queries
-        .p0()
+    let origin = |t: &GlobalTransform, offset: Option<&RotationOriginOffset>| {
+        t.translation()
+            + offset
+                .map(|o| t.compute_transform().rotation * o.0)
+                .unwrap_or(Vec3::ZERO)
+    };
+    let selection_query = queries.p0();
+    // Locked entities are ignored, unless the whole selection is locked.
+    let all_locked = selection_query
         .iter()
         .filter(|(s, ..)| s.is_selected)
-        .map(|(_s, t, offset)| {
-            t.translation()
-                + offset
-                    .map(|o| t.compute_transform().rotation * o.0)
-                    .unwrap_or(Vec3::ZERO)
-        })
+        .all(|(.., locked)| locked);
+    let placed = |(s, .., locked): &(&PickSelection, _, _, _, _, bool)| {
+        s.is_selected && (!locked || all_locked)
+    };
+    let active = active_selection
+        .0
+        .and_then(|entity| selection_query.get(entity).ok())
+        .filter(placed)
+        .map(|(_s, t, parent, offset, ..)| (*t, parent.map(|p| p.get()), origin(t, offset)));
+    let rotation = match (plugin_settings.space, active) {
+        (GizmoSpace::Local, Some((transform, ..))) => transform.to_scale_rotation_translation().1,
+        (GizmoSpace::Parent, Some((_, Some(parent), _))) => parent_query
+            .get(parent)
+            .map(|t| t.to_scale_rotation_translation().1)
+            .unwrap_or(plugin_settings.alignment_rotation),
+        _ => plugin_settings.alignment_rotation,
+    };
+    let selected: Vec<_> = selection_query
+        .iter()
+        .filter(placed)
+        .map(|(_s, t, _parent, offset, aabb, _)| (origin(t, offset), t, aabb))
         .collect();
     let n_selected = selected.len();
-    let transform_sum = selected.iter().fold(Vec3::ZERO, |acc, t| acc + *t);
+    let transform_sum = selected.iter().fold(Vec3::ZERO, |acc, (t, ..)| acc + *t);
     let centroid = transform_sum / n_selected as f32;
+    let pivot = match plugin_settings.pivot {
+        PivotMode::MedianPoint | PivotMode::IndividualOrigins => centroid,
+        PivotMode::BoundingBoxCenter => {
+            let (min, max) = selected.iter().fold(
+                (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+                |(min, max), (origin, transform, aabb)| match aabb {
+                    Some(aabb) => {
+                        let (aabb_min, aabb_max) = world_aabb(transform, aabb);
+                        (min.min(aabb_min), max.max(aabb_max))
+                    }
+                    None => (min.min(*origin), max.max(*origin)),
+                },
+            );
+            (min + max) / 2.0
+        }
+        PivotMode::ActiveElement => active.map(|(.., origin)| origin).unwrap_or(centroid),
+        PivotMode::Cursor => pivot_cursor.0,
+    };
     // Set the gizmo's position and visibility
     if let Ok((mut g_transform, mut transform, mut visible)) = queries.p1().get_single_mut() {
         let gt = g_transform.compute_transform();
         *g_transform = Transform {
-            translation: centroid,
-            rotation: plugin_settings.alignment_rotation,
+            translation: pivot,
+            rotation,
             ..gt
         }
         .into();
-        transform.translation = centroid;
-        transform.rotation = plugin_settings.alignment_rotation;
+        transform.translation = pivot;
+        transform.rotation = rotation;
         if n_selected > 0 {
             *visible = Visibility::Inherited;
         } else {
@@ -574,6 +1454,49 @@ fn place_gizmo(
     }
 }
 
+/// Computes the world space min and max corners of an entity's local `Aabb`.
+fn world_aabb(transform: &GlobalTransform, aabb: &Aabb) -> (Vec3, Vec3) {
+    let center = Vec3::from(aabb.center);
+    let half_extents = Vec3::from(aabb.half_extents);
+    [-1.0, 1.0]
+        .into_iter()
+        .flat_map(|x| [-1.0, 1.0].map(|y| Vec3::new(x, y, 0.0)))
+        .flat_map(|xy| [-1.0, 1.0].map(|z| xy.with_z(z)))
+        .map(|corner| transform.transform_point(center + corner * half_extents))
+        .fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), corner| (min.min(corner), max.max(corner)),
+        )
+}
+
+/// Makes the most recently selected entity the [`ActiveSelection`], and picks another selected
+/// entity when the active one is deselected.
+fn update_active_selection(
+    mut active_selection: ResMut<ActiveSelection>,
+    selection_query: Query<(Entity, Ref<PickSelection>), With<GizmoTransformable>>,
+) {
+    let newly_selected = selection_query
+        .iter()
+        .filter(|(_, s)| s.is_changed() && s.is_selected)
+        .map(|(entity, _)| entity)
+        .last();
+    if let Some(entity) = newly_selected {
+        active_selection.set_if_neq(ActiveSelection(Some(entity)));
+        return;
+    }
+    let active_is_selected = active_selection
+        .0
+        .and_then(|entity| selection_query.get(entity).ok())
+        .is_some_and(|(_, s)| s.is_selected);
+    if !active_is_selected {
+        let fallback = selection_query
+            .iter()
+            .find(|(_, s)| s.is_selected)
+            .map(|(entity, _)| entity);
+        active_selection.set_if_neq(ActiveSelection(fallback));
+    }
+}
+
 fn propagate_gizmo_elements(
     gizmo: Query<(&GlobalTransform, &Children), With<TransformGizmo>>,
     mut gizmo_parts_query: Query<(&Transform, &mut GlobalTransform), Without<TransformGizmo>>,
@@ -586,15 +1509,19 @@ fn propagate_gizmo_elements(
     }
 }
 
+/// Orients the handles' interaction axes to match the gizmo, and applies visibility settings.
 fn update_gizmo_settings(
     plugin_settings: Res<GizmoSettings>,
-    mut interactions: Query<&mut TransformGizmoInteraction, Without<ViewTranslateGizmo>>,
-    mut rotations: Query<&mut Visibility, With<RotationGizmo>>,
+    gizmo_query: Query<&Transform, With<TransformGizmo>>,
+    mut interactions: Query<&mut TransformGizmoInteraction, Without<ViewAlignedGizmo>>,
+    mut rotations: Query<&mut Visibility, (With<RotationGizmo>, Without<ScaleGizmo>)>,
+    mut scales: Query<&mut Visibility, (With<ScaleGizmo>, Without<RotationGizmo>)>,
 ) {
-    if !plugin_settings.is_changed() {
-        return;
-    }
-    let rotation = plugin_settings.alignment_rotation;
+    // The gizmo's rotation is set by `place_gizmo`, and depends on the `GizmoSpace`.
+    let rotation = match gizmo_query.get_single() {
+        Ok(transform) => transform.rotation,
+        Err(_) => plugin_settings.alignment_rotation,
+    };
     for mut interaction in interactions.iter_mut() {
         if let Some(rotated_interaction) = match *interaction {
             TransformGizmoInteraction::TranslateAxis { original, axis: _ } => {
@@ -622,11 +1549,25 @@ fn update_gizmo_settings(
                     axis: rotation.mul_vec3(original),
                 })
             }
+            TransformGizmoInteraction::ScalePlane {
+                original,
+                normal: _,
+            } => Some(TransformGizmoInteraction::ScalePlane {
+                original,
+                normal: rotation.mul_vec3(original),
+            }),
+            TransformGizmoInteraction::RotateFree
+            | TransformGizmoInteraction::ScaleUniform
+            | TransformGizmoInteraction::TranslateSurface => None,
         } {
-            *interaction = rotated_interaction;
+            interaction.set_if_neq(rotated_interaction);
         }
     }
 
+    if !plugin_settings.is_changed() {
+        return;
+    }
+
     for mut visibility in rotations.iter_mut() {
         if plugin_settings.allow_rotation {
             *visibility = Visibility::Inherited;
@@ -634,41 +1575,117 @@ fn update_gizmo_settings(
             *visibility = Visibility::Hidden;
         }
     }
+
+    for mut visibility in scales.iter_mut() {
+        if plugin_settings.allow_scale {
+            *visibility = Visibility::Inherited;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
 }
 
+/// Rotates the view aligned gizmo parts to face the camera, and points their interaction axes along
+/// the view direction.
 #[allow(clippy::type_complexity)]
-fn adjust_view_translate_gizmo(
-    mut gizmo: Query<
+fn adjust_view_aligned_gizmos(
+    mut view_aligned: Query<
         (&mut GlobalTransform, &mut TransformGizmoInteraction),
-        (With<ViewTranslateGizmo>, Without<GizmoPickSource>),
+        (With<ViewAlignedGizmo>, Without<GizmoPickSource>),
     >,
     camera: Query<&Transform, With<GizmoPickSource>>,
 ) {
-    let (mut global_transform, mut interaction) = match gizmo.get_single_mut() {
-        Ok(x) => x,
-        Err(_) => return,
-    };
-
     let cam_transform = match camera.get_single() {
         Ok(x) => x,
         Err(_) => return,
     };
 
     let direction = cam_transform.local_z();
-    *interaction = TransformGizmoInteraction::TranslatePlane {
-        original: Vec3::ZERO,
-        normal: *direction,
-    };
     let rotation = Quat::from_mat3(&Mat3::from_cols(
         direction.cross(*cam_transform.local_y()),
         *direction,
         *cam_transform.local_y(),
     ));
-    *global_transform = Transform {
+    for (mut global_transform, mut interaction) in view_aligned.iter_mut() {
+        match *interaction {
+            TransformGizmoInteraction::TranslatePlane { original, .. } => {
+                *interaction = TransformGizmoInteraction::TranslatePlane {
+                    original,
+                    normal: *direction,
+                };
+            }
+            TransformGizmoInteraction::RotateAxis { original, .. } => {
+                *interaction = TransformGizmoInteraction::RotateAxis {
+                    original,
+                    axis: *direction,
+                };
+            }
+            _ => (),
+        }
+        *global_transform = Transform {
+            rotation,
+            ..global_transform.compute_transform()
+        }
+        .into();
+    }
+}
+
+/// Shows the angle swept by an axis rotation drag as a filled arc, starting where the drag started.
+fn update_rotation_sweep(
+    gizmo_query: Query<(&TransformGizmo, &GlobalTransform)>,
+    mut sweep_query: Query<
+        (&Handle<Mesh>, &mut GlobalTransform, &mut Visibility),
+        (With<RotationSweepGizmo>, Without<TransformGizmo>),
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+    style: Res<GizmoStyle>,
+    mut last_angle: Local<f32>,
+) {
+    // The sweep mesh is rebuilt with no angle when the style changes.
+    if style.is_changed() {
+        *last_angle = 0.0;
+    }
+    let (Ok((gizmo, gizmo_transform)), Ok((mesh, mut transform, mut visibility))) =
+        (gizmo_query.get_single(), sweep_query.get_single_mut())
+    else {
+        return;
+    };
+    let sweep = match (
+        gizmo.current_interaction,
+        gizmo.drag_start,
+        gizmo.origin_drag_start,
+    ) {
+        (
+            Some(TransformGizmoInteraction::RotateAxis { axis, .. }),
+            Some(drag_start),
+            Some(origin),
+        ) if gizmo.is_active() => Some((axis.normalize(), drag_start, origin)),
+        _ => None,
+    };
+    let Some((axis, drag_start, origin)) = sweep else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+    // The sweep mesh starts at +X and rotates around +Y.
+    let rotation = Quat::from_mat3(&Mat3::from_cols(drag_start, axis, drag_start.cross(axis)));
+    *transform = Transform {
+        translation: origin,
         rotation,
-        ..global_transform.compute_transform()
+        scale: Vec3::splat(gizmo_transform.compute_transform().scale.x),
     }
     .into();
+    if *last_angle != gizmo.applied_rotation_angle {
+        *last_angle = gizmo.applied_rotation_angle;
+        meshes.insert(
+            mesh,
+            Mesh::from(mesh::pie_slice::PieSlice {
+                radius: style.arc_radius,
+                angle: gizmo.applied_rotation_angle,
+                ..Default::default()
+            }),
+        );
+    }
 }
 
 fn gizmo_cam_copy_settings(
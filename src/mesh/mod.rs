@@ -1,5 +1,6 @@
 use crate::{
-    gizmo_material::GizmoMaterial, InternalGizmoCamera, PickableGizmo, TransformGizmoBundle,
+    gizmo_material::GizmoMaterial, normalization::Normalize3d, style::GizmoStyle, GizmoSettings,
+    InternalGizmoCamera, PickableGizmo, TransformGizmo, TransformGizmoBundle,
     TransformGizmoInteraction,
 };
 use bevy::{
@@ -9,6 +10,7 @@ use bevy::{
 use bevy_mod_raycast::prelude::NoBackfaceCulling;
 
 mod cone;
+pub mod pie_slice;
 mod truncated_torus;
 
 #[derive(Component)]
@@ -17,51 +19,127 @@ pub struct RotationGizmo;
 #[derive(Component)]
 pub struct ViewTranslateGizmo;
 
-/// Startup system that builds the procedural mesh and materials of the gizmo.
-pub fn build_gizmo(
+#[derive(Component)]
+pub struct ScaleGizmo;
+
+/// Marks the filled arc that shows how far the selection has been rotated during a drag.
+#[derive(Component)]
+pub struct RotationSweepGizmo;
+
+/// Marks gizmo parts that are rotated every frame to face the camera.
+#[derive(Component)]
+pub struct ViewAlignedGizmo;
+
+/// Startup system that spawns the gizmo and the camera it is rendered with. Its handles are
+/// spawned by [`rebuild_gizmo`].
+pub fn build_gizmo(mut commands: Commands, style: Res<GizmoStyle>) {
+    commands.spawn(TransformGizmoBundle {
+        normalize: style.normalize(),
+        ..Default::default()
+    });
+
+    commands.spawn((
+        Camera3dBundle {
+            camera_3d: Camera3d {
+                depth_load_op: Camera3dDepthLoadOp::Clear(0.),
+                ..default()
+            },
+            camera: Camera {
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            ..Default::default()
+        },
+        InternalGizmoCamera,
+        RenderLayers::layer(12),
+    ));
+}
+
+/// Replaces the handles of the gizmo with ones built from the procedural meshes and materials
+/// described by the [`GizmoStyle`]. Runs whenever the style changes.
+pub fn rebuild_gizmo(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<GizmoMaterial>>,
+    style: Res<GizmoStyle>,
+    plugin_settings: Res<GizmoSettings>,
+    mut gizmo_query: Query<(Entity, &mut Normalize3d), With<TransformGizmo>>,
 ) {
-    let axis_length = 1.3;
-    let arc_radius = 1.;
+    let Ok((gizmo, mut normalize)) = gizmo_query.get_single_mut() else {
+        return;
+    };
+    *normalize = style.normalize();
+    // `update_gizmo_settings` only updates the visibility of the handles when the settings change,
+    // so new handles start out with the visibility the settings call for.
+    let visibility = |allowed: bool| match allowed {
+        true => Visibility::Inherited,
+        false => Visibility::Hidden,
+    };
+    let rotation_visibility = visibility(plugin_settings.allow_rotation);
+    let scale_visibility = visibility(plugin_settings.allow_scale);
+    let axis_length = style.axis_length;
+    let arc_radius = style.arc_radius;
+    // The view rotation and uniform scale rings are thinner than the other handles.
+    let ring_thickness = style.thickness * 0.75;
     let plane_size = axis_length * 0.25;
     let plane_offset = plane_size / 2. + axis_length * 0.2;
+    // Scale handles point along the negative axes, where they don't overlap the other handles.
+    let scale_length = axis_length * 0.8;
     // Define gizmo meshes
     let arrow_tail_mesh = meshes.add(Capsule3d {
-        radius: 0.04,
+        radius: style.thickness,
         half_length: axis_length * 0.5f32,
     });
     let cone_mesh = meshes.add(cone::Cone {
-        height: 0.25,
-        radius: 0.10,
+        height: style.cone_height,
+        radius: style.cone_radius,
         ..Default::default()
     });
     let plane_mesh = meshes.add(Plane3d::default().mesh().size(plane_size, plane_size));
-    let sphere_mesh = meshes.add(Sphere { radius: 0.2 });
+    let scale_plane_mesh = meshes.add(
+        Plane3d::default()
+            .mesh()
+            .size(plane_size * 0.6, plane_size * 0.6),
+    );
+    let sphere_mesh = meshes.add(Sphere {
+        radius: style.view_handle_radius,
+    });
+    let view_rotation_mesh = meshes.add(Mesh::from(truncated_torus::TruncatedTorus {
+        radius: arc_radius * 1.2,
+        ring_radius: ring_thickness,
+        angle: std::f32::consts::TAU,
+        ..Default::default()
+    }));
+    let scale_ring_mesh = meshes.add(Mesh::from(truncated_torus::TruncatedTorus {
+        radius: style.scale_ring_radius,
+        ring_radius: ring_thickness,
+        angle: std::f32::consts::TAU,
+        ..Default::default()
+    }));
     let rotation_mesh = meshes.add(Mesh::from(truncated_torus::TruncatedTorus {
         radius: arc_radius,
-        ring_radius: 0.04,
+        ring_radius: style.thickness,
         ..Default::default()
     }));
-    //let cube_mesh = meshes.add(Mesh::from(shape::Cube { size: 0.15 }));
+    let scale_tail_mesh = meshes.add(Capsule3d {
+        radius: style.thickness,
+        half_length: scale_length * 0.5f32,
+    });
+    let cube_mesh = meshes.add(Cuboid::from_length(style.scale_handle_size));
     // Define gizmo materials
-    let (s, l) = (0.8, 0.6);
-    let gizmo_matl_x = materials.add(GizmoMaterial::from(Color::hsl(0.0, s, l)));
-    let gizmo_matl_y = materials.add(GizmoMaterial::from(Color::hsl(120.0, s, l)));
-    let gizmo_matl_z = materials.add(GizmoMaterial::from(Color::hsl(240.0, s, l)));
-    let gizmo_matl_x_sel = materials.add(GizmoMaterial::from(Color::hsl(0.0, s, l)));
-    let gizmo_matl_y_sel = materials.add(GizmoMaterial::from(Color::hsl(120.0, s, l)));
-    let gizmo_matl_z_sel = materials.add(GizmoMaterial::from(Color::hsl(240.0, s, l)));
-    let gizmo_matl_v_sel = materials.add(GizmoMaterial::from(Color::hsl(0., 0.0, l)));
+    let gizmo_matl_x = materials.add(GizmoMaterial::from(style.x_color));
+    let gizmo_matl_y = materials.add(GizmoMaterial::from(style.y_color));
+    let gizmo_matl_z = materials.add(GizmoMaterial::from(style.z_color));
+    let gizmo_matl_v = materials.add(GizmoMaterial::from(style.view_color));
+    let gizmo_matl_sweep = materials.add(GizmoMaterial::from(style.sweep_color));
     /*let gizmo_matl_origin = materials.add(StandardMaterial {
         unlit: true,
         base_color: Color::rgb(0.7, 0.7, 0.7),
         ..Default::default()
     });*/
-    // Build the gizmo using the variables above.
     commands
-        .spawn(TransformGizmoBundle::default())
+        .entity(gizmo)
+        .despawn_descendants()
         .with_children(|parent| {
             // Translation Axes
             parent.spawn((
@@ -123,7 +201,7 @@ pub fn build_gizmo(
             parent.spawn((
                 MaterialMeshBundle {
                     mesh: cone_mesh.clone(),
-                    material: gizmo_matl_x_sel.clone(),
+                    material: gizmo_matl_x.clone(),
                     transform: Transform::from_matrix(Mat4::from_rotation_translation(
                         Quat::from_rotation_z(std::f32::consts::PI / -2.0),
                         Vec3::new(axis_length, 0.0, 0.0),
@@ -141,7 +219,7 @@ pub fn build_gizmo(
             parent.spawn((
                 MaterialMeshBundle {
                     mesh: plane_mesh.clone(),
-                    material: gizmo_matl_x_sel.clone(),
+                    material: gizmo_matl_x.clone(),
                     transform: Transform::from_matrix(Mat4::from_rotation_translation(
                         Quat::from_rotation_z(std::f32::consts::PI / -2.0),
                         Vec3::new(0., plane_offset, plane_offset),
@@ -160,7 +238,7 @@ pub fn build_gizmo(
             parent.spawn((
                 MaterialMeshBundle {
                     mesh: cone_mesh.clone(),
-                    material: gizmo_matl_y_sel.clone(),
+                    material: gizmo_matl_y.clone(),
                     transform: Transform::from_translation(Vec3::new(0.0, axis_length, 0.0)),
                     ..Default::default()
                 },
@@ -175,7 +253,7 @@ pub fn build_gizmo(
             parent.spawn((
                 MaterialMeshBundle {
                     mesh: plane_mesh.clone(),
-                    material: gizmo_matl_y_sel.clone(),
+                    material: gizmo_matl_y.clone(),
                     transform: Transform::from_translation(Vec3::new(
                         plane_offset,
                         0.0,
@@ -195,7 +273,7 @@ pub fn build_gizmo(
             parent.spawn((
                 MaterialMeshBundle {
                     mesh: cone_mesh.clone(),
-                    material: gizmo_matl_z_sel.clone(),
+                    material: gizmo_matl_z.clone(),
                     transform: Transform::from_matrix(Mat4::from_rotation_translation(
                         Quat::from_rotation_x(std::f32::consts::PI / 2.0),
                         Vec3::new(0.0, 0.0, axis_length),
@@ -213,7 +291,7 @@ pub fn build_gizmo(
             parent.spawn((
                 MaterialMeshBundle {
                     mesh: plane_mesh.clone(),
-                    material: gizmo_matl_z_sel.clone(),
+                    material: gizmo_matl_z.clone(),
                     transform: Transform::from_matrix(Mat4::from_rotation_translation(
                         Quat::from_rotation_x(std::f32::consts::PI / 2.0),
                         Vec3::new(plane_offset, plane_offset, 0.0),
@@ -233,7 +311,7 @@ pub fn build_gizmo(
             parent.spawn((
                 MaterialMeshBundle {
                     mesh: sphere_mesh.clone(),
-                    material: gizmo_matl_v_sel.clone(),
+                    material: gizmo_matl_v.clone(),
                     ..Default::default()
                 },
                 PickableGizmo::default(),
@@ -242,6 +320,203 @@ pub fn build_gizmo(
                     normal: Vec3::Z,
                 },
                 ViewTranslateGizmo,
+                ViewAlignedGizmo,
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: scale_ring_mesh,
+                    material: gizmo_matl_v.clone(),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScaleUniform,
+                ViewAlignedGizmo,
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+
+            // Scale Axes
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: scale_tail_mesh.clone(),
+                    material: gizmo_matl_x.clone(),
+                    transform: Transform::from_matrix(Mat4::from_rotation_translation(
+                        Quat::from_rotation_z(std::f32::consts::PI / 2.0),
+                        Vec3::new(-scale_length / 2.0, 0.0, 0.0),
+                    )),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScaleAxis {
+                    original: Vec3::X,
+                    axis: Vec3::X,
+                },
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: scale_tail_mesh.clone(),
+                    material: gizmo_matl_y.clone(),
+                    transform: Transform::from_translation(Vec3::new(
+                        0.0,
+                        -scale_length / 2.0,
+                        0.0,
+                    )),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScaleAxis {
+                    original: Vec3::Y,
+                    axis: Vec3::Y,
+                },
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: scale_tail_mesh,
+                    material: gizmo_matl_z.clone(),
+                    transform: Transform::from_matrix(Mat4::from_rotation_translation(
+                        Quat::from_rotation_x(std::f32::consts::PI / 2.0),
+                        Vec3::new(0.0, 0.0, -scale_length / 2.0),
+                    )),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScaleAxis {
+                    original: Vec3::Z,
+                    axis: Vec3::Z,
+                },
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+
+            // Scale Handles
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: cube_mesh.clone(),
+                    material: gizmo_matl_x.clone(),
+                    transform: Transform::from_translation(Vec3::new(-scale_length, 0.0, 0.0)),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScaleAxis {
+                    original: Vec3::X,
+                    axis: Vec3::X,
+                },
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: cube_mesh.clone(),
+                    material: gizmo_matl_y.clone(),
+                    transform: Transform::from_translation(Vec3::new(0.0, -scale_length, 0.0)),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScaleAxis {
+                    original: Vec3::Y,
+                    axis: Vec3::Y,
+                },
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: cube_mesh.clone(),
+                    material: gizmo_matl_z.clone(),
+                    transform: Transform::from_translation(Vec3::new(0.0, 0.0, -scale_length)),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScaleAxis {
+                    original: Vec3::Z,
+                    axis: Vec3::Z,
+                },
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+
+            // Scale Planes
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: scale_plane_mesh.clone(),
+                    material: gizmo_matl_x.clone(),
+                    transform: Transform::from_matrix(Mat4::from_rotation_translation(
+                        Quat::from_rotation_z(std::f32::consts::PI / -2.0),
+                        Vec3::new(0., -plane_offset, -plane_offset),
+                    )),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScalePlane {
+                    original: Vec3::X,
+                    normal: Vec3::X,
+                },
+                NoBackfaceCulling,
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: scale_plane_mesh.clone(),
+                    material: gizmo_matl_y.clone(),
+                    transform: Transform::from_translation(Vec3::new(
+                        -plane_offset,
+                        0.0,
+                        -plane_offset,
+                    )),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScalePlane {
+                    original: Vec3::Y,
+                    normal: Vec3::Y,
+                },
+                NoBackfaceCulling,
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: scale_plane_mesh,
+                    material: gizmo_matl_z.clone(),
+                    transform: Transform::from_matrix(Mat4::from_rotation_translation(
+                        Quat::from_rotation_x(std::f32::consts::PI / 2.0),
+                        Vec3::new(-plane_offset, -plane_offset, 0.0),
+                    )),
+                    visibility: scale_visibility,
+                    ..Default::default()
+                },
+                ScaleGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::ScalePlane {
+                    original: Vec3::Z,
+                    normal: Vec3::Z,
+                },
+                NoBackfaceCulling,
                 NotShadowCaster,
                 RenderLayers::layer(12),
             ));
@@ -255,6 +530,7 @@ pub fn build_gizmo(
                         Vec3::Z,
                         f32::to_radians(90.0),
                     )),
+                    visibility: rotation_visibility,
                     ..Default::default()
                 },
                 RotationGizmo,
@@ -270,6 +546,7 @@ pub fn build_gizmo(
                 MaterialMeshBundle {
                     mesh: rotation_mesh.clone(),
                     material: gizmo_matl_y.clone(),
+                    visibility: rotation_visibility,
                     ..Default::default()
                 },
                 RotationGizmo,
@@ -289,6 +566,7 @@ pub fn build_gizmo(
                         Quat::from_axis_angle(Vec3::Z, f32::to_radians(90.0))
                             * Quat::from_axis_angle(Vec3::X, f32::to_radians(90.0)),
                     ),
+                    visibility: rotation_visibility,
                     ..Default::default()
                 },
                 RotationGizmo,
@@ -300,21 +578,39 @@ pub fn build_gizmo(
                 NotShadowCaster,
                 RenderLayers::layer(12),
             ));
-        });
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: view_rotation_mesh,
+                    material: gizmo_matl_v.clone(),
+                    visibility: rotation_visibility,
+                    ..Default::default()
+                },
+                RotationGizmo,
+                PickableGizmo::default(),
+                TransformGizmoInteraction::RotateAxis {
+                    original: Vec3::ZERO,
+                    axis: Vec3::Z,
+                },
+                ViewAlignedGizmo,
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
 
-    commands.spawn((
-        Camera3dBundle {
-            camera_3d: Camera3d {
-                depth_load_op: Camera3dDepthLoadOp::Clear(0.),
-                ..default()
-            },
-            camera: Camera {
-                clear_color: ClearColorConfig::None,
-                ..default()
-            },
-            ..Default::default()
-        },
-        InternalGizmoCamera,
-        RenderLayers::layer(12),
-    ));
+            // Rotation Sweep, rebuilt while rotating
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: meshes.add(pie_slice::PieSlice {
+                        radius: arc_radius,
+                        angle: 0.0,
+                        ..Default::default()
+                    }),
+                    material: gizmo_matl_sweep,
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                RotationSweepGizmo,
+                NotShadowCaster,
+                RenderLayers::layer(12),
+            ));
+        });
 }
@@ -0,0 +1,71 @@
+use bevy::{
+    prelude::*,
+    render::{mesh::Indices, render_asset::RenderAssetUsages, render_resource::PrimitiveTopology},
+};
+/// A flat, filled circular sector in the XZ plane, starting at +X and sweeping `angle` radians
+/// around +Y.
+#[derive(Debug, Clone, Copy)]
+pub struct PieSlice {
+    pub radius: f32,
+    pub angle: f32,
+    /// Number of segments used for a full circle.
+    pub subdivisions: usize,
+}
+
+impl Default for PieSlice {
+    fn default() -> Self {
+        PieSlice {
+            radius: 1.0,
+            angle: std::f32::consts::PI / 2.0,
+            subdivisions: 64,
+        }
+    }
+}
+
+impl From<PieSlice> for Mesh {
+    fn from(slice: PieSlice) -> Self {
+        let angle = slice
+            .angle
+            .clamp(-std::f32::consts::TAU, std::f32::consts::TAU);
+        let n_segments = ((angle.abs() / std::f32::consts::TAU * slice.subdivisions as f32).ceil()
+            as usize)
+            .max(1);
+        let segment_stride = angle / n_segments as f32;
+
+        let n_vertices = n_segments + 2;
+        let mut positions: Vec<[f32; 3]> = Vec::with_capacity(n_vertices);
+        let mut normals: Vec<[f32; 3]> = Vec::with_capacity(n_vertices);
+        let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(n_vertices);
+
+        // Center
+        positions.push([0.0, 0.0, 0.0]);
+        normals.push(Vec3::Y.into());
+        uvs.push([0.5, 0.5]);
+
+        for segment in 0..=n_segments {
+            // Positive angles rotate +X towards -Z, matching `Quat::from_rotation_y`.
+            let theta = segment_stride * segment as f32;
+            let (sin, cos) = theta.sin_cos();
+            positions.push([cos * slice.radius, 0.0, -sin * slice.radius]);
+            normals.push(Vec3::Y.into());
+            uvs.push([0.5 + cos * 0.5, 0.5 - sin * 0.5]);
+        }
+
+        let mut indices: Vec<u32> = Vec::with_capacity(n_segments * 3);
+        for segment in 0..n_segments {
+            indices.push(0);
+            indices.push(segment as u32 + 1);
+            indices.push(segment as u32 + 2);
+        }
+
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_indices(Indices::U32(indices));
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh
+    }
+}
@@ -0,0 +1,282 @@
+use bevy::prelude::*;
+use bevy_mod_picking::{prelude::PickingInteraction, selection::PickSelection};
+
+use crate::{
+    constraints::GizmoLocked, end_drag, history::GizmoHistory, restore_initial_transforms,
+    ActiveSelection, GizmoPickSource, GizmoSettings, GizmoTransformable, InitialTransform,
+    RotationOriginOffset, TransformGizmo, TransformGizmoCancelEvent, TransformGizmoEvent,
+    TransformGizmoInteraction,
+};
+
+/// Keys that start a modal transform. None are set by default, since keys such as `S` are often
+/// used by camera controls. A modal transform moves the selection with the cursor without grabbing
+/// a handle, until it is confirmed with `Enter` or a left click, or cancelled with `Escape` or a
+/// right click. While it is active, `X`, `Y`, and `Z` constrain it to an axis.
+/// Pressing the same axis again uses the active entity's local axis, and a third time removes the
+/// constraint. Holding `Shift` constrains to the plane perpendicular to the axis instead. The keys
+/// are ignored while `Ctrl`, `Alt`, or `Super` is held, so they don't clash with shortcuts such as
+/// `Ctrl+S`.
+#[derive(Clone, Debug, Default)]
+pub struct ModalKeys {
+    pub translate: Option<KeyCode>,
+    pub rotate: Option<KeyCode>,
+    pub scale: Option<KeyCode>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModalMode {
+    Translate,
+    Rotate,
+    Scale,
+}
+
+/// State of an in-progress modal transform.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ModalTransform {
+    mode: ModalMode,
+    constraint: Option<ModalConstraint>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ModalConstraint {
+    /// Constrained axis, in the space chosen by `local`.
+    axis: Vec3,
+    /// Constrain to the plane perpendicular to `axis`, rather than to `axis` itself.
+    exclude: bool,
+    /// Use the active entity's axes rather than the gizmo's.
+    local: bool,
+}
+
+impl ModalTransform {
+    pub(crate) fn mode(&self) -> ModalMode {
+        self.mode
+    }
+
+    fn interaction(
+        &self,
+        gizmo_rotation: Quat,
+        local_rotation: Quat,
+        view_direction: Vec3,
+    ) -> TransformGizmoInteraction {
+        let Some(constraint) = self.constraint else {
+            return match self.mode {
+                ModalMode::Translate => TransformGizmoInteraction::TranslatePlane {
+                    original: Vec3::ZERO,
+                    normal: view_direction,
+                },
+                ModalMode::Rotate => TransformGizmoInteraction::RotateAxis {
+                    original: Vec3::ZERO,
+                    axis: view_direction,
+                },
+                ModalMode::Scale => TransformGizmoInteraction::ScaleUniform,
+            };
+        };
+        let original = constraint.axis;
+        let axis = match constraint.local {
+            true => local_rotation * original,
+            false => gizmo_rotation * original,
+        };
+        match (self.mode, constraint.exclude) {
+            (ModalMode::Translate, false) => {
+                TransformGizmoInteraction::TranslateAxis { original, axis }
+            }
+            (ModalMode::Translate, true) => TransformGizmoInteraction::TranslatePlane {
+                original,
+                normal: axis,
+            },
+            (ModalMode::Rotate, _) => TransformGizmoInteraction::RotateAxis { original, axis },
+            (ModalMode::Scale, false) => TransformGizmoInteraction::ScaleAxis { original, axis },
+            (ModalMode::Scale, true) => TransformGizmoInteraction::ScalePlane {
+                original,
+                normal: axis,
+            },
+        }
+    }
+
+    /// Cycles the constraint for `axis`: gizmo axis, then local axis, then unconstrained.
+    fn toggle_constraint(&mut self, axis: Vec3, exclude: bool) {
+        self.constraint = match self.constraint {
+            Some(c) if c.axis == axis && c.exclude == exclude && !c.local => {
+                Some(ModalConstraint { local: true, ..c })
+            }
+            Some(c) if c.axis == axis && c.exclude == exclude => None,
+            _ => Some(ModalConstraint {
+                axis,
+                exclude,
+                local: false,
+            }),
+        };
+    }
+}
+
+/// Starts, constrains, and confirms modal transforms from keyboard input.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn modal_transform(
+    mut commands: Commands,
+    plugin_settings: Res<GizmoSettings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut mouse_button_input: ResMut<ButtonInput<MouseButton>>,
+    active_selection: Res<ActiveSelection>,
+    camera_query: Query<&GlobalTransform, With<GizmoPickSource>>,
+    mut gizmo_events: EventWriter<TransformGizmoEvent>,
+    mut cancel_events: EventWriter<TransformGizmoCancelEvent>,
+    mut history: ResMut<GizmoHistory>,
+    mut gizmo_query: Query<(
+        &mut TransformGizmo,
+        &PickingInteraction,
+        &GlobalTransform,
+        &Transform,
+    )>,
+    mut selected_items_query: Query<
+        (
+            Entity,
+            &PickSelection,
+            &GlobalTransform,
+            &mut Transform,
+            Option<&RotationOriginOffset>,
+            Option<&InitialTransform>,
+        ),
+        (With<GizmoTransformable>, Without<TransformGizmo>),
+    >,
+    locked_query: Query<(), With<GizmoLocked>>,
+) {
+    let Ok((mut gizmo, interaction, gizmo_global_transform, gizmo_transform)) =
+        gizmo_query.get_single_mut()
+    else {
+        return;
+    };
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    let view_direction = *camera_transform.back();
+    let gizmo_rotation = gizmo_transform.rotation;
+    let local_rotation = active_selection
+        .0
+        .and_then(|entity| selected_items_query.get(entity).ok())
+        .map(|(_, _, transform, ..)| transform.to_scale_rotation_translation().1)
+        .unwrap_or(gizmo_rotation);
+
+    let Some(mut modal) = gizmo.modal else {
+        if *interaction == PickingInteraction::Pressed {
+            return;
+        }
+        if keys.any_pressed([
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::AltLeft,
+            KeyCode::AltRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ]) {
+            return;
+        }
+        let modal_keys = &plugin_settings.modal_keys;
+        let just_pressed = |key: Option<KeyCode>| key.is_some_and(|key| keys.just_pressed(key));
+        let mode = if just_pressed(modal_keys.translate) {
+            ModalMode::Translate
+        } else if just_pressed(modal_keys.rotate) {
+            ModalMode::Rotate
+        } else if just_pressed(modal_keys.scale) {
+            ModalMode::Scale
+        } else {
+            return;
+        };
+        let mut any_selected = false;
+        for (entity, selection, transform, local_transform, rotation_origin_offset, _) in
+            selected_items_query.iter()
+        {
+            if selection.is_selected && !locked_query.contains(entity) {
+                any_selected = true;
+                commands.entity(entity).insert(InitialTransform {
+                    transform: transform.compute_transform(),
+                    local_transform: *local_transform,
+                    rotation_offset: rotation_origin_offset
+                        .map(|offset| offset.0)
+                        .unwrap_or(Vec3::ZERO),
+                });
+            }
+        }
+        if !any_selected {
+            return;
+        }
+        let modal = ModalTransform {
+            mode,
+            constraint: None,
+        };
+        *gizmo = TransformGizmo {
+            current_interaction: Some(modal.interaction(
+                gizmo_rotation,
+                local_rotation,
+                view_direction,
+            )),
+            modal: Some(modal),
+            ..Default::default()
+        };
+        return;
+    };
+
+    if keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter])
+        || mouse_button_input.just_pressed(MouseButton::Left)
+    {
+        // Stop the click from also grabbing a handle or changing the selection.
+        mouse_button_input.clear_just_pressed(MouseButton::Left);
+        end_drag(
+            &mut commands,
+            plugin_settings.transform_hook,
+            &mut history,
+            &mut gizmo_events,
+            &mut cancel_events,
+            &gizmo,
+            gizmo_global_transform,
+            selected_items_query.iter_mut().filter_map(
+                |(entity, _, _, transform, _, initial_transform)| {
+                    Some((entity, transform, initial_transform?))
+                },
+            ),
+        );
+        for (entity, .., initial_transform) in selected_items_query.iter() {
+            if initial_transform.is_some() {
+                commands.entity(entity).remove::<InitialTransform>();
+            }
+        }
+        *gizmo = TransformGizmo::default();
+        return;
+    }
+
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let mut constraint_changed = false;
+    for (key, axis) in [
+        (KeyCode::KeyX, Vec3::X),
+        (KeyCode::KeyY, Vec3::Y),
+        (KeyCode::KeyZ, Vec3::Z),
+    ] {
+        if keys.just_pressed(key) {
+            modal.toggle_constraint(axis, shift);
+            constraint_changed = true;
+        }
+    }
+    if constraint_changed {
+        // Restart the transform from the current cursor position with the new constraint.
+        restore_initial_transforms(
+            &mut commands,
+            plugin_settings.transform_hook,
+            selected_items_query.iter_mut().filter_map(
+                |(entity, _, _, transform, _, initial_transform)| {
+                    Some((entity, transform, initial_transform?))
+                },
+            ),
+        );
+        *gizmo = TransformGizmo {
+            current_interaction: Some(modal.interaction(
+                gizmo_rotation,
+                local_rotation,
+                view_direction,
+            )),
+            modal: Some(modal),
+            origin_drag_start: gizmo.origin_drag_start,
+            initial_transform: gizmo.initial_transform,
+            numeric_input: gizmo.numeric_input.take(),
+            ..Default::default()
+        };
+    }
+}
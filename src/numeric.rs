@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::PickingInteraction;
+
+use crate::{
+    end_drag, history::GizmoHistory, GizmoSettings, InitialTransform, TransformGizmo,
+    TransformGizmoCancelEvent, TransformGizmoEvent,
+};
+
+/// A value typed on the keyboard during a drag. Depending on the interaction, it is a distance
+/// along the axis, an angle in degrees, or a scale factor. Interactions without a single degree of
+/// freedom, such as plane translation and free rotation, ignore it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct NumericInput {
+    text: String,
+}
+
+impl NumericInput {
+    /// Text typed so far, including the sign.
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Parsed value, or `None` if nothing that parses as a number has been typed yet.
+    pub(crate) fn value(&self) -> Option<f32> {
+        self.text.parse().ok()
+    }
+
+    /// Applies a key press. Keys that aren't used for numeric entry are ignored.
+    fn press(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Minus | KeyCode::NumpadSubtract => {
+                // Minus negates the value, even after digits have been typed.
+                match self.text.strip_prefix('-') {
+                    Some(rest) => self.text = rest.to_string(),
+                    None => self.text.insert(0, '-'),
+                }
+            }
+            KeyCode::Period | KeyCode::NumpadDecimal => {
+                if !self.text.contains('.') {
+                    self.text.push('.');
+                }
+            }
+            KeyCode::Backspace => {
+                self.text.pop();
+            }
+            key => {
+                if let Some(digit) = digit(key) {
+                    self.text.push(digit);
+                }
+            }
+        }
+    }
+}
+
+fn digit(key: KeyCode) -> Option<char> {
+    let digit = match key {
+        KeyCode::Digit0 | KeyCode::Numpad0 => '0',
+        KeyCode::Digit1 | KeyCode::Numpad1 => '1',
+        KeyCode::Digit2 | KeyCode::Numpad2 => '2',
+        KeyCode::Digit3 | KeyCode::Numpad3 => '3',
+        KeyCode::Digit4 | KeyCode::Numpad4 => '4',
+        KeyCode::Digit5 | KeyCode::Numpad5 => '5',
+        KeyCode::Digit6 | KeyCode::Numpad6 => '6',
+        KeyCode::Digit7 | KeyCode::Numpad7 => '7',
+        KeyCode::Digit8 | KeyCode::Numpad8 => '8',
+        KeyCode::Digit9 | KeyCode::Numpad9 => '9',
+        _ => return None,
+    };
+    Some(digit)
+}
+
+/// Builds a [`NumericInput`] from keys typed while the gizmo is dragged. Pressing `Enter` commits
+/// a handle drag with the typed value without waiting for the mouse button to be released. Modal
+/// transforms are committed by [`crate::modal`].
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn numeric_entry(
+    mut commands: Commands,
+    plugin_settings: Res<GizmoSettings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut gizmo_events: EventWriter<TransformGizmoEvent>,
+    mut cancel_events: EventWriter<TransformGizmoCancelEvent>,
+    mut history: ResMut<GizmoHistory>,
+    mut gizmo_query: Query<(
+        &mut TransformGizmo,
+        &mut PickingInteraction,
+        &GlobalTransform,
+    )>,
+    mut initial_transform_query: Query<(Entity, &mut Transform, &InitialTransform)>,
+) {
+    for (mut gizmo, mut interaction, transform) in gizmo_query.iter_mut() {
+        if !gizmo.is_active() {
+            continue;
+        }
+        let mut input = gizmo.numeric_input.take().unwrap_or_default();
+        for key in keys.get_just_pressed() {
+            input.press(*key);
+        }
+        // Deleting every character hands control back to the cursor.
+        gizmo.numeric_input = (!input.text().is_empty()).then_some(input);
+        if gizmo.numeric_input.is_some() {
+            // Typing a value starts the drag, even if the cursor hasn't moved past the drag
+            // threshold.
+            gizmo.press_cursor = None;
+        }
+        // Until `drag_gizmo` has started the drag, there is nothing to commit yet, so `Enter` is
+        // ignored and the typed value is kept.
+        if gizmo.modal.is_some()
+            || gizmo.numeric_input.is_none()
+            || gizmo.initial_transform.is_none()
+            || !keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter])
+        {
+            continue;
+        }
+        end_drag(
+            &mut commands,
+            plugin_settings.transform_hook,
+            &mut history,
+            &mut gizmo_events,
+            &mut cancel_events,
+            &gizmo,
+            transform,
+            initial_transform_query.iter_mut(),
+        );
+        for (entity, ..) in initial_transform_query.iter() {
+            commands.entity(entity).remove::<InitialTransform>();
+        }
+        // Ignore the rest of the drag until the mouse button is released.
+        *gizmo = TransformGizmo {
+            drag_ended: true,
+            ..Default::default()
+        };
+        *interaction = PickingInteraction::None;
+    }
+}
@@ -0,0 +1,149 @@
+use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+
+/// Configures how gizmo interactions snap to fixed increments.
+#[derive(Clone, Debug)]
+pub struct GizmoSnapping {
+    /// Whether snapping is applied while no toggle key is held.
+    pub enabled: bool,
+    /// Holding any of these keys inverts `enabled` for as long as it is held.
+    pub toggle_keys: Vec<KeyCode>,
+    /// Increment, in world units, that translations snap to.
+    pub translation_increment: f32,
+    /// Whether translations snap the drag delta or the resulting position.
+    pub translation_mode: SnapMode,
+    /// Increment, in radians, that rotations snap to.
+    pub rotation_increment: f32,
+    /// Holding any of these keys when grabbing the view translate handle makes the selection
+    /// follow the cursor over the surface of pickable meshes.
+    pub surface_keys: Vec<KeyCode>,
+    /// Whether surface snapping rotates the selection so its up axis matches the surface normal.
+    pub align_to_surface: bool,
+    /// Holding this key while translating moves the selection so that its
+    /// [`vertex_source`](Self::vertex_source) lands on the mesh vertex nearest to the cursor.
+    pub vertex_key: Option<KeyCode>,
+    /// Point of the selection that is snapped onto the target vertex.
+    pub vertex_source: VertexSnapSource,
+}
+
+impl Default for GizmoSnapping {
+    fn default() -> Self {
+        GizmoSnapping {
+            enabled: false,
+            toggle_keys: vec![KeyCode::ControlLeft, KeyCode::ControlRight],
+            translation_increment: 1.0,
+            translation_mode: SnapMode::Relative,
+            rotation_increment: 15f32.to_radians(),
+            surface_keys: vec![KeyCode::AltLeft, KeyCode::AltRight],
+            align_to_surface: false,
+            vertex_key: Some(KeyCode::KeyV),
+            vertex_source: VertexSnapSource::Pivot,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SnapMode {
+    /// Snap the distance dragged, keeping the selection's offset from the grid.
+    #[default]
+    Relative,
+    /// Snap the resulting position of the gizmo onto a grid aligned with the gizmo's axes.
+    Absolute,
+}
+
+/// The point of the selection that vertex snapping moves onto the target vertex.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VertexSnapSource {
+    /// The gizmo's pivot point.
+    #[default]
+    Pivot,
+    /// The vertex of the selected meshes that is nearest to the target vertex.
+    NearestVertex,
+}
+
+impl GizmoSnapping {
+    /// Returns `true` if snapping should be applied given the current keyboard state.
+    pub fn is_active(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        let toggled = keys.any_pressed(self.toggle_keys.iter().copied());
+        self.enabled != toggled
+    }
+
+    /// Snaps a world space `translation` of the gizmo away from `origin`. Snapping happens on the
+    /// gizmo's axes, described by `rotation`, and only on the axes selected by `axes_mask`.
+    pub(crate) fn snap_translation(
+        &self,
+        origin: Vec3,
+        translation: Vec3,
+        rotation: Quat,
+        axes_mask: Vec3,
+    ) -> Vec3 {
+        if self.translation_increment <= 0.0 {
+            return translation;
+        }
+        let inverse_rotation = rotation.inverse();
+        let local_translation = inverse_rotation * translation;
+        let snapped = match self.translation_mode {
+            SnapMode::Relative => snap(local_translation, self.translation_increment),
+            SnapMode::Absolute => {
+                let local_origin = inverse_rotation * origin;
+                snap(local_origin + local_translation, self.translation_increment) - local_origin
+            }
+        };
+        let mask = axes_mask.cmpgt(Vec3::ZERO);
+        rotation * Vec3::select(mask, snapped, local_translation)
+    }
+
+    /// Snaps a world space `translation` of the gizmo away from `origin`, along `axis`. The result
+    /// always lies on `axis`.
+    pub(crate) fn snap_translation_along(
+        &self,
+        origin: Vec3,
+        translation: Vec3,
+        axis: Vec3,
+    ) -> Vec3 {
+        let axis = axis.normalize();
+        let distance = translation.dot(axis);
+        if self.translation_increment <= 0.0 {
+            return axis * distance;
+        }
+        let snapped = match self.translation_mode {
+            SnapMode::Relative => snap_scalar(distance, self.translation_increment),
+            SnapMode::Absolute => {
+                let origin = origin.dot(axis);
+                snap_scalar(origin + distance, self.translation_increment) - origin
+            }
+        };
+        axis * snapped
+    }
+
+    /// Snaps a rotation angle, in radians, to a multiple of the rotation increment.
+    pub(crate) fn snap_angle(&self, angle: f32) -> f32 {
+        if self.rotation_increment <= 0.0 {
+            return angle;
+        }
+        (angle / self.rotation_increment).round() * self.rotation_increment
+    }
+}
+
+/// Returns the world space position of the vertex of `mesh` that is nearest to `point`.
+pub(crate) fn nearest_vertex(mesh: &Mesh, mesh_to_world: &Mat4, point: Vec3) -> Option<Vec3> {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+    positions
+        .iter()
+        .map(|position| mesh_to_world.transform_point3(Vec3::from(*position)))
+        .min_by(|a, b| {
+            a.distance_squared(point)
+                .total_cmp(&b.distance_squared(point))
+        })
+}
+
+fn snap(value: Vec3, increment: f32) -> Vec3 {
+    (value / increment).round() * increment
+}
+
+fn snap_scalar(value: f32, increment: f32) -> f32 {
+    (value / increment).round() * increment
+}
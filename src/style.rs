@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+use crate::normalization::Normalize3d;
+
+/// Sizes and colors of the gizmo. Sizes are in gizmo units, before the gizmo is scaled to a
+/// constant size on screen by [`Normalize3d`]. The handle meshes and materials are rebuilt
+/// whenever this resource changes.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct GizmoStyle {
+    /// Length of the translation axes. The scale axes and the translate and scale planes are sized
+    /// relative to it.
+    pub axis_length: f32,
+    /// Radius of the axis stems and the rotation arcs.
+    pub thickness: f32,
+    pub cone_height: f32,
+    pub cone_radius: f32,
+    /// Radius of the rotation arcs. The view rotation ring is slightly larger.
+    pub arc_radius: f32,
+    /// Radius of the view translate handle at the center of the gizmo.
+    pub view_handle_radius: f32,
+    /// Radius of the uniform scale ring.
+    pub scale_ring_radius: f32,
+    /// Edge length of the cubes at the ends of the scale axes.
+    pub scale_handle_size: f32,
+    pub x_color: Color,
+    pub y_color: Color,
+    pub z_color: Color,
+    /// Color of the view aligned handles.
+    pub view_color: Color,
+    /// Color of the arc swept out during an axis rotation.
+    pub sweep_color: Color,
+    /// Color of every part of the gizmo while the transforms of a drag are rejected.
+    pub rejected_color: Color,
+    /// Color of handles that can't change any of the selected entities.
+    pub locked_color: Color,
+    /// Lightness added to the color of the hovered or dragged handle.
+    pub highlight_lightness: f32,
+    /// Lightness removed from the color of the other handles while one is dragged.
+    pub dim_lightness: f32,
+    /// See [`Normalize3d::size_in_world`].
+    pub size_in_world: f32,
+    /// See [`Normalize3d::desired_pixel_size`].
+    pub pixel_size: f32,
+}
+
+impl Default for GizmoStyle {
+    fn default() -> Self {
+        let (s, l) = (0.8, 0.6);
+        GizmoStyle {
+            axis_length: 1.3,
+            thickness: 0.04,
+            cone_height: 0.25,
+            cone_radius: 0.10,
+            arc_radius: 1.0,
+            view_handle_radius: 0.2,
+            scale_ring_radius: 0.32,
+            scale_handle_size: 0.15,
+            x_color: Color::hsl(0.0, s, l),
+            y_color: Color::hsl(120.0, s, l),
+            z_color: Color::hsl(240.0, s, l),
+            view_color: Color::hsl(0.0, 0.0, l),
+            sweep_color: Color::hsla(0.0, 0.0, 0.9, 0.3),
+            rejected_color: Color::hsl(0.0, 1.0, 0.45),
+            locked_color: Color::hsl(0.0, 0.0, 0.35),
+            highlight_lightness: 0.2,
+            dim_lightness: 0.3,
+            size_in_world: 1.5,
+            pixel_size: 150.0,
+        }
+    }
+}
+
+impl GizmoStyle {
+    pub fn normalize(&self) -> Normalize3d {
+        Normalize3d::new(self.size_in_world, self.pixel_size)
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::{GizmoSettings, TransformGizmo, TransformTarget};
+
+/// Result of [`GizmoSettings::transform_validator`] for a proposed transform.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransformValidation {
+    /// Apply the proposed transform.
+    Accept,
+    /// Apply this world space transform instead of the proposed one.
+    Clamp(Transform),
+    /// Keep the selection at its last accepted transforms. Releasing the drag while it is rejected
+    /// restores the selection to its transforms from before the drag.
+    Reject,
+}
+
+/// Transforms computed by `drag_gizmo` this frame, waiting to be validated and applied.
+#[derive(Resource, Default)]
+pub(crate) struct ProposedTransforms(pub Vec<TransformTarget>);
+
+impl TransformTarget {
+    /// Replaces the world space transform of the target, adjusting its local transform to match.
+    fn with_global(self, global: Transform) -> Self {
+        let parent = self.global.compute_matrix() * self.local.compute_matrix().inverse();
+        TransformTarget {
+            entity: self.entity,
+            local: Transform::from_matrix(parent.inverse() * global.compute_matrix()),
+            global,
+        }
+    }
+}
+
+/// Runs the proposed transforms through the validator, then writes them to the entities or hands
+/// them to the transform hook. If any of them is rejected, none of them are applied.
+pub(crate) fn apply_proposed_transforms(world: &mut World) {
+    let targets = std::mem::take(&mut world.resource_mut::<ProposedTransforms>().0);
+    if targets.is_empty() {
+        return;
+    }
+    let settings = world.resource::<GizmoSettings>();
+    let (validator, transform_hook) = (settings.transform_validator, settings.transform_hook);
+
+    let mut accepted = Vec::with_capacity(targets.len());
+    let mut rejected = false;
+    for target in targets {
+        let validation = match validator {
+            Some(validator) => match world.run_system_with_input(validator, target) {
+                Ok(validation) => validation,
+                Err(error) => {
+                    error!("Failed to run the transform validator: {error:?}");
+                    TransformValidation::Accept
+                }
+            },
+            None => TransformValidation::Accept,
+        };
+        match validation {
+            TransformValidation::Accept => accepted.push(target),
+            TransformValidation::Clamp(global) => accepted.push(target.with_global(global)),
+            TransformValidation::Reject => rejected = true,
+        }
+    }
+    let mut gizmo_query = world.query::<&mut TransformGizmo>();
+    for mut gizmo in gizmo_query.iter_mut(world) {
+        if gizmo.rejected != rejected {
+            gizmo.rejected = rejected;
+        }
+    }
+    if rejected {
+        return;
+    }
+
+    match transform_hook {
+        Some(transform_hook) => {
+            if let Err(error) = world.run_system_with_input(transform_hook, accepted) {
+                error!("Failed to run the transform hook: {error:?}");
+            }
+        }
+        None => {
+            for target in accepted {
+                if let Some(mut transform) = world.get_mut::<Transform>(target.entity) {
+                    transform.set_if_neq(target.local);
+                }
+            }
+        }
+    }
+}